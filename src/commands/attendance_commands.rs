@@ -0,0 +1,113 @@
+use poise::serenity_prelude::{self as serenity, Mentionable};
+
+use crate::commands::autocomplete::autocomplete_event;
+use crate::commands::{event_id, reply};
+use crate::storage::event_store;
+use crate::structs::attendance::AttendanceStatus;
+use crate::structs::client_structs::{Context, Error};
+use crate::structs::event::{EventState, RsvpStatus};
+use crate::structs::event_error::EventError;
+use crate::util::permissions::is_organizer;
+
+/// How many past events `/attendance member` lists.
+const HISTORY_LENGTH: usize = 10;
+
+/// See who showed up to events
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("attendance_event", "attendance_member")
+)]
+pub async fn attendance(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Show who checked in to an event
+#[poise::command(slash_command, rename = "event")]
+pub async fn attendance_event(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_event"] event: String,
+) -> Result<(), Error> {
+    let event = event_store(ctx.serenity_context())
+        .await
+        .event(event_id(&event)?)?
+        .filter(|event| ctx.guild_id() == Some(event.server_id()))
+        .ok_or(EventError::NotFound)?;
+    let lines = match event.state() {
+        EventState::Completed => event
+            .attendance()
+            .iter()
+            .map(|record| format!("{}: {}", record.user_id.mention(), record.status.as_str()))
+            .collect::<Vec<String>>(),
+        _ => event
+            .participants()
+            .iter()
+            .filter(|prt| prt.status != RsvpStatus::Declined)
+            .map(|prt| match prt.checked_in {
+                Some(at) => format!("{}: checked in <t:{}:t>", prt.id.mention(), at.timestamp()),
+                None => format!("{}: not checked in", prt.id.mention()),
+            })
+            .collect::<Vec<String>>(),
+    };
+    if lines.is_empty() {
+        return reply(ctx, "Nobody signed up for this event.").await;
+    }
+    reply(ctx, format!("**{}**\n{}", event.title, lines.join("\n"))).await
+}
+
+/// Show how often a member attended, came late or did not show up
+#[poise::command(slash_command, rename = "member")]
+pub async fn attendance_member(
+    ctx: Context<'_>,
+    #[description = "Leave empty for yourself"] member: Option<serenity::User>,
+) -> Result<(), Error> {
+    let store = event_store(ctx.serenity_context()).await;
+    let guild_id = ctx.guild_id().unwrap();
+    let member = member.unwrap_or_else(|| ctx.author().clone());
+    if member.id != ctx.author().id && !is_organizer(ctx, &store.guild_config(guild_id)?).await {
+        return reply(
+            ctx,
+            "Only members with the event organizer role or admins can see the attendance of others.",
+        )
+        .await;
+    }
+    let records = store.attendance(guild_id, member.id)?;
+    if records.is_empty() {
+        return reply(
+            ctx,
+            format!("{} has no finished events yet.", member.mention()),
+        )
+        .await;
+    }
+    let count = |status| {
+        records
+            .iter()
+            .filter(|record| record.status == status)
+            .count()
+    };
+    let recent = records
+        .iter()
+        .take(HISTORY_LENGTH)
+        .map(|record| {
+            format!(
+                "<t:{}:d> **{}**: {}",
+                record.date.timestamp(),
+                record.title,
+                record.status.as_str()
+            )
+        })
+        .collect::<Vec<String>>();
+    reply(
+        ctx,
+        format!(
+            "{} attended {}, came late to {} and missed {} of {} events.\n{}",
+            member.mention(),
+            count(AttendanceStatus::Attended),
+            count(AttendanceStatus::Late),
+            count(AttendanceStatus::NoShow),
+            records.len(),
+            recent.join("\n")
+        ),
+    )
+    .await
+}
@@ -0,0 +1,90 @@
+use chrono_tz::TZ_VARIANTS;
+use poise::serenity_prelude::AutocompleteChoice;
+
+use crate::storage::event_store;
+use crate::structs::client_structs::Context;
+use crate::structs::event::Event;
+use crate::util::event_parser::DATE_FORMAT;
+use crate::util::timezone::Clock;
+
+async fn event_choices(
+    ctx: Context<'_>,
+    partial: &str,
+    filter: impl Fn(&Event) -> bool,
+) -> Vec<AutocompleteChoice> {
+    let store = event_store(ctx.serenity_context()).await;
+    let events = match ctx.guild_id() {
+        Some(guild_id) => store.events(guild_id).unwrap_or_default(),
+        None => Vec::new(),
+    };
+    let zone = Clock::of(store.as_ref(), ctx.author().id, ctx.guild_id())
+        .unwrap_or_default()
+        .zone;
+    let partial = partial.to_lowercase();
+    events
+        .into_iter()
+        .filter(|ev| filter(ev) && ev.title.to_lowercase().contains(&partial))
+        .take(25)
+        .map(|ev| {
+            AutocompleteChoice::new(
+                format!(
+                    "{} ({})",
+                    ev.title,
+                    ev.date.with_timezone(&zone).format(DATE_FORMAT)
+                ),
+                ev.id.to_string(),
+            )
+        })
+        .collect()
+}
+
+/// Suggests the events of the current server that belong to a series.
+pub async fn autocomplete_series_event(
+    ctx: Context<'_>,
+    partial: &str,
+) -> impl Iterator<Item = AutocompleteChoice> {
+    event_choices(ctx, partial, |ev| ev.series.is_some())
+        .await
+        .into_iter()
+}
+
+/// Suggests the events of the current server.
+pub async fn autocomplete_event(
+    ctx: Context<'_>,
+    partial: &str,
+) -> impl Iterator<Item = AutocompleteChoice> {
+    event_choices(ctx, partial, |_| true).await.into_iter()
+}
+
+/// Suggests IANA timezone names.
+pub async fn autocomplete_timezone(
+    _ctx: Context<'_>,
+    partial: &str,
+) -> impl Iterator<Item = AutocompleteChoice> {
+    let partial = partial.to_lowercase();
+    TZ_VARIANTS
+        .iter()
+        .filter(move |zone| zone.name().to_lowercase().contains(&partial))
+        .take(25)
+        .map(|zone| AutocompleteChoice::new(zone.name(), zone.name()))
+}
+
+/// Suggests the names of the templates of the current server.
+pub async fn autocomplete_template(
+    ctx: Context<'_>,
+    partial: &str,
+) -> impl Iterator<Item = AutocompleteChoice> {
+    let templates = match ctx.guild_id() {
+        Some(guild_id) => event_store(ctx.serenity_context())
+            .await
+            .templates(guild_id)
+            .unwrap_or_default(),
+        None => Vec::new(),
+    };
+    let partial = partial.to_lowercase();
+    templates
+        .into_iter()
+        .filter(move |template| template.name.to_lowercase().contains(&partial))
+        .take(25)
+        .map(|template| AutocompleteChoice::new(template.name.clone(), template.name))
+}
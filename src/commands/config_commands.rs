@@ -0,0 +1,298 @@
+use chrono::Locale;
+use chrono_tz::Tz;
+use poise::serenity_prelude::{self as serenity, Color, Mentionable};
+
+use crate::commands::autocomplete::autocomplete_timezone;
+use crate::commands::reply;
+use crate::storage::event_store;
+use crate::structs::client_structs::{Context, Error};
+use crate::structs::guild_config::GuildConfig;
+use crate::util::event_parser::{parse_duration, parse_offsets};
+
+/// Change the event settings of this server
+#[poise::command(
+    slash_command,
+    guild_only,
+    default_member_permissions = "MANAGE_GUILD",
+    required_permissions = "MANAGE_GUILD",
+    subcommands(
+        "config_show",
+        "config_channel",
+        "config_timezone",
+        "config_locale",
+        "config_reminders",
+        "config_organizer_role",
+        "config_color",
+        "config_own_channel",
+        "config_channel_category",
+        "config_archive_category",
+        "config_channel_cleanup",
+        "config_log_channel"
+    )
+)]
+pub async fn config(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Loads the settings of the server, lets `change` edit them and stores the result.
+async fn update_config(
+    ctx: Context<'_>,
+    change: impl FnOnce(&mut GuildConfig) -> Result<String, String>,
+) -> Result<(), Error> {
+    let store = event_store(ctx.serenity_context()).await;
+    let mut config = store.guild_config(ctx.guild_id().unwrap())?;
+    match change(&mut config) {
+        Ok(confirmation) => {
+            store.save_guild_config(&config)?;
+            reply(ctx, confirmation).await
+        }
+        Err(err) => reply(ctx, err).await,
+    }
+}
+
+/// Show the current settings
+#[poise::command(slash_command, rename = "show")]
+pub async fn config_show(ctx: Context<'_>) -> Result<(), Error> {
+    let config = event_store(ctx.serenity_context())
+        .await
+        .guild_config(ctx.guild_id().unwrap())?;
+    let unset = || String::from("not set");
+    let reminders = config
+        .reminder_offsets
+        .iter()
+        .map(|offset| format!("{}m", offset.num_minutes()))
+        .collect::<Vec<String>>()
+        .join(", ");
+    reply(
+        ctx,
+        format!(
+            "Event channel: {}\nTimezone: {}\nLocale: {}\nReminders: {}\nOrganizer role: {}\nEmbed color: #{}\nOwn channel per event: {}\nEvent channel category: {}\nArchive category: {}\nChannel cleanup: {}m after the event\nLog channel: {}",
+            config
+                .event_channel
+                .map_or_else(unset, |channel| channel.mention().to_string()),
+            config
+                .timezone
+                .map_or_else(unset, |zone| zone.name().to_string()),
+            config.locale,
+            if reminders.is_empty() { String::from("none") } else { reminders },
+            config
+                .organizer_role
+                .map_or_else(unset, |role| role.mention().to_string()),
+            config.embed_color.hex(),
+            if config.own_channel { "yes" } else { "no" },
+            config
+                .channel_category
+                .map_or_else(unset, |channel| channel.mention().to_string()),
+            config
+                .archive_category
+                .map_or_else(|| String::from("none, channels are deleted"), |channel| {
+                    channel.mention().to_string()
+                }),
+            config.channel_cleanup.num_minutes(),
+            config
+                .log_channel
+                .map_or_else(unset, |channel| channel.mention().to_string())
+        ),
+    )
+    .await
+}
+
+/// Set the channel new events are posted to
+#[poise::command(slash_command, rename = "channel")]
+pub async fn config_channel(
+    ctx: Context<'_>,
+    #[description = "Leave empty to post where the command is used"]
+    #[channel_types("Text")]
+    channel: Option<serenity::GuildChannel>,
+) -> Result<(), Error> {
+    update_config(ctx, |config| {
+        config.event_channel = channel.as_ref().map(|channel| channel.id);
+        Ok(match &channel {
+            Some(channel) => format!("New events will be posted in {}.", channel.mention()),
+            None => String::from("New events will be posted where they are created."),
+        })
+    })
+    .await
+}
+
+/// Set the timezone for members who have not picked their own
+#[poise::command(slash_command, rename = "timezone")]
+pub async fn config_timezone(
+    ctx: Context<'_>,
+    #[description = "IANA timezone, e.g. Europe/Berlin. Leave empty for the bot's zone"]
+    #[autocomplete = "autocomplete_timezone"]
+    zone: Option<String>,
+) -> Result<(), Error> {
+    update_config(ctx, |config| {
+        config.timezone = zone
+            .as_deref()
+            .map(|zone| {
+                Tz::from_str_insensitive(zone.trim()).map_err(|_| {
+                    format!("`{zone}` is not a known timezone. Use a name like `Europe/Berlin`.")
+                })
+            })
+            .transpose()?;
+        Ok(match config.timezone {
+            Some(zone) => format!("The server timezone is now {}.", zone.name()),
+            None => String::from("The server timezone has been cleared."),
+        })
+    })
+    .await
+}
+
+/// Set the language dates are written in
+#[poise::command(slash_command, rename = "locale")]
+pub async fn config_locale(
+    ctx: Context<'_>,
+    #[description = "e.g. en_US or de_DE"] locale: String,
+) -> Result<(), Error> {
+    update_config(ctx, |config| {
+        config.locale = locale
+            .trim()
+            .replace('-', "_")
+            .parse::<Locale>()
+            .map_err(|_| {
+                format!("`{locale}` is not a known locale. Use e.g. `en_US` or `de_DE`.")
+            })?;
+        Ok(format!("Dates are now written in {}.", config.locale))
+    })
+    .await
+}
+
+/// Set when participants of new events are reminded
+#[poise::command(slash_command, rename = "reminders")]
+pub async fn config_reminders(
+    ctx: Context<'_>,
+    #[description = "e.g. 24h, 15m, or none"] reminders: String,
+) -> Result<(), Error> {
+    update_config(ctx, |config| {
+        config.reminder_offsets = match reminders.trim().eq_ignore_ascii_case("none") {
+            true => Vec::new(),
+            false => parse_offsets(&reminders)?,
+        };
+        Ok(String::from("The default reminders have been updated."))
+    })
+    .await
+}
+
+/// Set the role whose members may organize events
+#[poise::command(slash_command, rename = "organizer_role")]
+pub async fn config_organizer_role(
+    ctx: Context<'_>,
+    #[description = "Leave empty to remove the organizer role"] role: Option<serenity::Role>,
+) -> Result<(), Error> {
+    update_config(ctx, |config| {
+        config.organizer_role = role.as_ref().map(|role| role.id);
+        Ok(match &role {
+            Some(role) => format!("{} is now the organizer role.", role.mention()),
+            None => String::from("The organizer role has been removed."),
+        })
+    })
+    .await
+}
+
+/// Set the color of open event embeds
+#[poise::command(slash_command, rename = "color")]
+pub async fn config_color(
+    ctx: Context<'_>,
+    #[description = "Hex color, e.g. #9b59b6"] color: String,
+) -> Result<(), Error> {
+    update_config(ctx, |config| {
+        config.embed_color = u32::from_str_radix(color.trim().trim_start_matches('#'), 16)
+            .ok()
+            .filter(|value| *value <= 0xFFFFFF)
+            .map(Color::new)
+            .ok_or(format!("`{color}` is not a hex color like `#9b59b6`."))?;
+        Ok(format!(
+            "New events will use #{}.",
+            config.embed_color.hex()
+        ))
+    })
+    .await
+}
+
+/// Set whether every new event gets a channel of its own
+#[poise::command(slash_command, rename = "own_channel")]
+pub async fn config_own_channel(ctx: Context<'_>, enabled: bool) -> Result<(), Error> {
+    update_config(ctx, |config| {
+        config.own_channel = enabled;
+        Ok(String::from(match enabled {
+            true => "New events will get a channel of their own.",
+            false => "New events will be posted to the event channel.",
+        }))
+    })
+    .await
+}
+
+/// Set the category the channels of new events are created in
+#[poise::command(slash_command, rename = "channel_category")]
+pub async fn config_channel_category(
+    ctx: Context<'_>,
+    #[description = "Leave empty to create them outside of any category"]
+    #[channel_types("Category")]
+    category: Option<serenity::GuildChannel>,
+) -> Result<(), Error> {
+    update_config(ctx, |config| {
+        config.channel_category = category.as_ref().map(|category| category.id);
+        Ok(match &category {
+            Some(category) => format!("Event channels will be created in {}.", category.name),
+            None => String::from("Event channels will be created outside of any category."),
+        })
+    })
+    .await
+}
+
+/// Set the category the channels of past events are moved to
+#[poise::command(slash_command, rename = "archive_category")]
+pub async fn config_archive_category(
+    ctx: Context<'_>,
+    #[description = "Leave empty to delete the channels instead"]
+    #[channel_types("Category")]
+    category: Option<serenity::GuildChannel>,
+) -> Result<(), Error> {
+    update_config(ctx, |config| {
+        config.archive_category = category.as_ref().map(|category| category.id);
+        Ok(match &category {
+            Some(category) => format!(
+                "The channels of past events will be moved to {} and made read only.",
+                category.name
+            ),
+            None => String::from("The channels of past events will be deleted."),
+        })
+    })
+    .await
+}
+
+/// Set how long after an event its channel is archived or deleted
+#[poise::command(slash_command, rename = "channel_cleanup")]
+pub async fn config_channel_cleanup(
+    ctx: Context<'_>,
+    #[description = "e.g. 24h or 90m"] after: String,
+) -> Result<(), Error> {
+    update_config(ctx, |config| {
+        config.channel_cleanup = parse_duration(&after)?;
+        Ok(format!(
+            "Event channels will be cleaned up {} minutes after the event.",
+            config.channel_cleanup.num_minutes()
+        ))
+    })
+    .await
+}
+
+/// Set the channel transcripts of deleted event channels are posted to
+#[poise::command(slash_command, rename = "log_channel")]
+pub async fn config_log_channel(
+    ctx: Context<'_>,
+    #[description = "Leave empty to delete event channels without a transcript"]
+    #[channel_types("Text")]
+    channel: Option<serenity::GuildChannel>,
+) -> Result<(), Error> {
+    update_config(ctx, |config| {
+        config.log_channel = channel.as_ref().map(|channel| channel.id);
+        Ok(match &channel {
+            Some(channel) => format!("Transcripts will be posted in {}.", channel.mention()),
+            None => String::from("Event channels will be deleted without a transcript."),
+        })
+    })
+    .await
+}
@@ -0,0 +1,264 @@
+use std::time::Duration;
+
+use chrono::Utc;
+
+use crate::storage::event_store;
+use crate::structs::client_structs::Error;
+use crate::structs::event::{Event, EventState, Placement, RsvpStatus, LATE_AFTER};
+use crate::structs::event_button::EventButton;
+use crate::structs::event_error::EventError;
+use crate::util::event::{notify_promoted, publish, WAITLIST_REPLY};
+use crate::util::thread::sync_thread_member;
+use poise::serenity_prelude::{
+    ComponentInteraction, ComponentInteractionDataKind, Context, CreateInteractionResponse,
+    CreateInteractionResponseMessage, CreateSelectMenu, CreateSelectMenuKind,
+    CreateSelectMenuOption,
+};
+
+/// Routes clicks on the buttons of a posted event embed.
+pub async fn handle_event_button(
+    ctx: &Context,
+    interaction: &ComponentInteraction,
+) -> Result<(), Error> {
+    let Some((button, event_id)) = EventButton::parse(&interaction.data.custom_id) else {
+        return Ok(());
+    };
+    let result = match (event_store(ctx).await.event(event_id)?, button) {
+        (None, _) => Err(EventError::NotFound.into()),
+        // Check-ins go on after the event stopped taking answers.
+        (Some(event), EventButton::CheckIn) => check_in(ctx, interaction, event).await,
+        (Some(event), _) if event.state() != EventState::Open => {
+            Err(EventError::NotOpen(event.state()).into())
+        }
+        (Some(event), EventButton::SignUp) => {
+            answer(ctx, interaction, event, RsvpStatus::Accepted).await
+        }
+        (Some(event), EventButton::Tentative) => {
+            answer(ctx, interaction, event, RsvpStatus::Tentative).await
+        }
+        (Some(event), EventButton::Late) => answer(ctx, interaction, event, RsvpStatus::Late).await,
+        (Some(event), EventButton::Decline) => {
+            answer(ctx, interaction, event, RsvpStatus::Declined).await
+        }
+        (Some(event), EventButton::SignOff) => sign_off(ctx, interaction, event).await,
+    };
+    // Refused changes are explained to the member instead of failing the handler.
+    match result {
+        Err(err) => match err.downcast_ref::<EventError>() {
+            Some(err) => respond(ctx, interaction, &err.to_string()).await,
+            None => Err(err),
+        },
+        ok => ok,
+    }
+}
+
+/// Signs the member up with the given RSVP, or changes the RSVP they already
+/// gave while keeping their role.
+async fn answer(
+    ctx: &Context,
+    interaction: &ComponentInteraction,
+    event: Event,
+    status: RsvpStatus,
+) -> Result<(), Error> {
+    if status == RsvpStatus::Declined && !event.contains_participant(&interaction.user) {
+        // Saying no needs no role.
+        let (event, ()) = event_store(ctx).await.update(event.id, |event| {
+            Ok(event.decline(false, interaction.user.clone())?)
+        })?;
+        publish(ctx, &event).await?;
+        return respond(
+            ctx,
+            interaction,
+            "Your answer has been recorded as declined.",
+        )
+        .await;
+    }
+    if !event.contains_participant(&interaction.user)
+        || (status != RsvpStatus::Declined && event.declined_without_role(&interaction.user))
+    {
+        return sign_up(ctx, interaction, event, status).await;
+    }
+    let (event, (placement, promoted)) = event_store(ctx).await.update(event.id, |event| {
+        Ok(event.set_status(false, &interaction.user, status)?)
+    })?;
+    publish(ctx, &event).await?;
+    sync_thread_member(ctx, &event, interaction.user.id).await;
+    notify_promoted(ctx, &event, &promoted).await;
+    let reply = match placement {
+        Placement::Roster => format!("Your answer has been changed to {}.", status.as_str()),
+        Placement::Waitlist => String::from(WAITLIST_REPLY),
+    };
+    respond(ctx, interaction, &reply).await
+}
+
+async fn respond(
+    ctx: &Context,
+    interaction: &ComponentInteraction,
+    content: &str,
+) -> Result<(), Error> {
+    interaction
+        .create_response(
+            ctx,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .content(content)
+                    .ephemeral(true),
+            ),
+        )
+        .await?;
+    Ok(())
+}
+
+fn selected_value(interaction: &ComponentInteraction) -> Option<String> {
+    match &interaction.data.kind {
+        ComponentInteractionDataKind::StringSelect { values } => values.first().cloned(),
+        _ => None,
+    }
+}
+
+/// Builds a select menu from `(label, value)` pairs.
+fn select_menu(custom_id: &str, options: &[(String, String)]) -> CreateSelectMenu {
+    CreateSelectMenu::new(
+        custom_id,
+        CreateSelectMenuKind::String {
+            options: options
+                .iter()
+                .map(|(label, value)| CreateSelectMenuOption::new(label, value))
+                .collect(),
+        },
+    )
+}
+
+async fn sign_up(
+    ctx: &Context,
+    interaction: &ComponentInteraction,
+    event: Event,
+    status: RsvpStatus,
+) -> Result<(), Error> {
+    let possible_roles = event
+        .roles()
+        .into_iter()
+        .map(|rl| (event.role_label(&rl), rl))
+        .collect::<Vec<(String, String)>>();
+    interaction
+        .create_response(
+            ctx,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .content("Please pick a role for the event.")
+                    .select_menu(select_menu("role_select", &possible_roles))
+                    .ephemeral(true),
+            ),
+        )
+        .await?;
+    let picker = interaction.get_response(ctx).await?;
+    let Some(role_interaction) = picker
+        .await_component_interaction(&ctx.shard)
+        .timeout(Duration::from_secs(60))
+        .await
+    else {
+        return Ok(());
+    };
+    let role_selection = selected_value(&role_interaction).unwrap_or_default();
+    let possible_flavors = event
+        .flavors(&role_selection)
+        .into_iter()
+        .map(|fl| (event.flavor_label(&role_selection, &fl), fl))
+        .collect::<Vec<(String, String)>>();
+    let (flavor_selection, last_interaction) = if possible_flavors.is_empty() {
+        (String::new(), role_interaction)
+    } else {
+        role_interaction
+            .create_response(
+                ctx,
+                CreateInteractionResponse::UpdateMessage(
+                    CreateInteractionResponseMessage::new()
+                        .content("Please pick a flavor for the event.")
+                        .select_menu(select_menu("flavor_select", &possible_flavors)),
+                ),
+            )
+            .await?;
+        let Some(flavor_interaction) = picker
+            .await_component_interaction(&ctx.shard)
+            .timeout(Duration::from_secs(60))
+            .await
+        else {
+            return Ok(());
+        };
+        (
+            selected_value(&flavor_interaction).unwrap_or_default(),
+            flavor_interaction,
+        )
+    };
+
+    // The roster may have changed while the picker was open, so the change is
+    // made on the stored event.
+    let signed_up = event_store(ctx).await.update(event.id, |event| {
+        Ok(event.add_participant(
+            false,
+            interaction.user.clone(),
+            role_selection,
+            flavor_selection,
+            status,
+        )?)
+    });
+    let reply = match signed_up {
+        Ok((event, placement)) => {
+            publish(ctx, &event).await?;
+            sync_thread_member(ctx, &event, interaction.user.id).await;
+            String::from(match placement {
+                Placement::Roster if status == RsvpStatus::Declined => {
+                    "Thanks for letting us know."
+                }
+                Placement::Roster => "Thank you for signing up!",
+                Placement::Waitlist => WAITLIST_REPLY,
+            })
+        }
+        Err(err) => match err.downcast_ref::<EventError>() {
+            Some(err) => err.to_string(),
+            None => return Err(err),
+        },
+    };
+    last_interaction
+        .create_response(
+            ctx,
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new()
+                    .content(reply)
+                    .components(vec![]),
+            ),
+        )
+        .await?;
+    Ok(())
+}
+
+async fn sign_off(
+    ctx: &Context,
+    interaction: &ComponentInteraction,
+    event: Event,
+) -> Result<(), Error> {
+    let (event, promoted) = event_store(ctx).await.update(event.id, |event| {
+        Ok(event.remove_participant(false, interaction.user.clone())?)
+    })?;
+    publish(ctx, &event).await?;
+    sync_thread_member(ctx, &event, interaction.user.id).await;
+    notify_promoted(ctx, &event, &promoted).await;
+    respond(ctx, interaction, "You have been removed from the event.").await
+}
+
+async fn check_in(
+    ctx: &Context,
+    interaction: &ComponentInteraction,
+    event: Event,
+) -> Result<(), Error> {
+    let now = Utc::now();
+    let (event, ()) = event_store(ctx).await.update(event.id, |event| {
+        Ok(event.check_in(interaction.user.id, now)?)
+    })?;
+    publish(ctx, &event).await?;
+    let reply = match now <= event.date() + LATE_AFTER {
+        true => "You are checked in. Have fun!",
+        false => "You are checked in, but late.",
+    };
+    respond(ctx, interaction, reply).await
+}
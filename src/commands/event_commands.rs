@@ -1,137 +1,442 @@
 use std::time::Duration;
 
-use crate::structs::client_structs::{Context, Error, EventData};
-use crate::structs::event::Event;
-use crate::util::event::{extract_datetime, extract_flavor, extract_role};
-use poise;
+use crate::commands::autocomplete::{autocomplete_event, autocomplete_template};
+use crate::commands::{event_id, reply};
+use crate::storage::event_store;
+use crate::structs::client_structs::{ApplicationContext, Context, Error};
+use crate::structs::event::{Event, EventState, Placement, RsvpStatus};
+use crate::structs::event_error::EventError;
+use crate::structs::event_modal::EventModal;
+use crate::structs::recurrence::Frequency;
+use crate::structs::series::Series;
+use crate::util::channels::{clean_up_channel, create_event_channel};
+use crate::util::date_parser::parse_date;
+use crate::util::event::{
+    notify_cancelled, notify_promoted, notify_roster_changes, publish, WAITLIST_REPLY,
+};
+use crate::util::event_parser::{
+    check_flavors, parse_duration, parse_flavors, parse_offsets, parse_recurrence, parse_roles,
+    retain_flavors,
+};
+use crate::util::permissions::{can_manage, is_organizer};
+use crate::util::scheduled_event::{delete_scheduled_event, sync_scheduled_event};
+use crate::util::thread::{archive_thread, close_thread, open_thread, sync_thread_member};
+use crate::util::timezone::Clock;
+use crate::util::voice::close_voice_channel;
+use chrono::Utc;
 use poise::serenity_prelude::{
-    Color, ComponentInteractionDataKind, CreateChannel, CreateEmbed, MessageFlags,
+    self as serenity, ComponentInteractionDataKind, CreateActionRow, CreateButton, Mentionable,
+    MessageFlags,
 };
 use poise::serenity_prelude::{
     CreateMessage, CreateSelectMenu, CreateSelectMenuKind, CreateSelectMenuOption,
 };
+use poise::{self, CreateReply};
+
+/// How long a member gets to fill in the event form.
+const FORM_TIMEOUT: Duration = Duration::from_secs(600);
 
 /// Create an Event
-#[poise::command(slash_command, prefix_command)]
-pub async fn create_event(ctx: Context<'_>, create_new_channel: Option<bool>) -> Result<(), Error> {
-    ctx.say("Thinking...").await?.delete(ctx).await?;
-    let private_cannel = ctx.author().create_dm_channel(ctx.http()).await?;
-    private_cannel
-        .say(ctx.http(), "How would you like to name the Event?")
+#[poise::command(slash_command, guild_only)]
+#[allow(clippy::too_many_arguments)]
+pub async fn create_event(
+    ctx: ApplicationContext<'_>,
+    create_new_channel: Option<bool>,
+    repeat: Option<Frequency>,
+    #[description = "Repeat every N days or weeks"] every: Option<u32>,
+    #[description = "Weekdays of a weekly event, e.g. tue, thu"] weekdays: Option<String>,
+    #[description = "Last day of the series as YYYY-MM-DD"] until: Option<String>,
+    #[description = "Number of occurrences in the series"] occurrences: Option<u32>,
+    #[description = "When to remind participants, e.g. 24h, 15m"] reminders: Option<String>,
+    #[description = "Send reminders as DMs (default: yes)"] dm_reminders: Option<bool>,
+    #[description = "Role to ping with reminders"] ping_role: Option<serenity::Role>,
+    #[description = "Length of the event, e.g. 2h or 90m"] duration: Option<String>,
+    #[description = "Post the event without opening sign-ups yet"] draft: Option<bool>,
+    #[description = "Saved template to fill in roles, flavors, description and duration"]
+    #[autocomplete = "autocomplete_template"]
+    template: Option<String>,
+    #[description = "Also create a Discord event members can mark as interested"]
+    discord_event: Option<bool>,
+    #[description = "Open a discussion thread on the event instead of a new channel"]
+    thread: Option<bool>,
+    #[description = "Open a voice channel for the participants shortly before the start"]
+    voice: Option<bool>,
+    #[description = "Let the waitlist into the voice channel too"] voice_waitlist: Option<bool>,
+    #[description = "Check in participants who are in voice around the start"]
+    auto_check_in: Option<bool>,
+) -> Result<(), Error> {
+    let store = event_store(ctx.serenity_context).await;
+    let guild_id = ctx.guild_id().unwrap();
+    let config = store.guild_config(guild_id)?;
+    if !is_organizer(ctx.into(), &config).await {
+        ctx.send(
+            CreateReply::default()
+                .content("Only members with the event organizer role or admins can create events.")
+                .ephemeral(true),
+        )
         .await?;
-    let event_name = {
-        let message_stream = private_cannel
-            .id
-            .await_reply(ctx.serenity_context().shard.clone());
-        message_stream.next().await.unwrap().content
-    };
-    let event_date = extract_datetime(private_cannel.id, &ctx).await;
-    let mut event = Event::new(
-        ctx.author().clone(),
-        event_name,
-        event_date,
-        ctx.guild_id().unwrap(),
-    );
-    match extract_role(private_cannel.id, ctx.serenity_context(), Some(())).await {
-        Some(role) => event.add_role(role),
-        None => {
-            ctx.author()
-                .dm(
-                    ctx.http(),
-                    CreateMessage::new().content("Could not create event."),
+        return Ok(());
+    }
+    let template = match template {
+        Some(name) => match store.template(guild_id, &name)? {
+            Some(template) => Some(template),
+            None => {
+                ctx.send(
+                    CreateReply::default()
+                        .content(format!("There is no template called `{name}`."))
+                        .ephemeral(true),
                 )
                 .await?;
+                return Ok(());
+            }
+        },
+        None => None,
+    };
+    let zone = Clock::of(store.as_ref(), ctx.author().id, Some(guild_id))?.zone;
+    let options = repeat
+        .map(|frequency| {
+            parse_recurrence(
+                frequency,
+                every,
+                weekdays.as_deref(),
+                until.as_deref(),
+                occurrences,
+                zone,
+            )
+        })
+        .transpose()
+        .and_then(|recurrence| {
+            Ok((
+                recurrence,
+                reminders.as_deref().map(parse_offsets).transpose()?,
+                duration.as_deref().map(parse_duration).transpose()?,
+            ))
+        });
+    let (recurrence, reminder_offsets, duration) = match options {
+        Ok(options) => options,
+        Err(err) => {
+            ctx.send(CreateReply::default().content(err).ephemeral(true))
+                .await?;
             return Ok(());
         }
+    };
+    let defaults = template.as_ref().map(EventModal::from_template);
+    let Some(mut form) =
+        poise::execute_modal::<_, _, EventModal>(ctx, defaults, Some(FORM_TIMEOUT)).await?
+    else {
+        return Ok(());
+    };
+    let ctx = Context::from(ctx);
+    let mut event = loop {
+        let checked = form
+            .to_event(ctx.author().clone(), guild_id, zone)
+            .and_then(|event| match &recurrence {
+                Some(recurrence) => match recurrence.check_start(event.date) {
+                    Ok(()) => Ok(event),
+                    Err(err) => Err(vec![err]),
+                },
+                None => Ok(event),
+            });
+        let errors = match checked {
+            Ok(event) => break event,
+            Err(errors) => errors,
+        };
+        let edit_id = format!("{}_edit_form", ctx.id());
+        let reply = ctx
+            .send(
+                CreateReply::default()
+                    .content(format!(
+                        "The event could not be created:\n{}",
+                        errors
+                            .iter()
+                            .map(|err| format!("- {err}"))
+                            .collect::<Vec<String>>()
+                            .join("\n")
+                    ))
+                    .components(vec![CreateActionRow::Buttons(vec![CreateButton::new(
+                        &edit_id,
+                    )
+                    .label("Edit")])])
+                    .ephemeral(true),
+            )
+            .await?;
+        let Some(interaction) = reply
+            .message()
+            .await?
+            .await_component_interaction(&ctx.serenity_context().shard)
+            .timeout(FORM_TIMEOUT)
+            .await
+        else {
+            return Ok(());
+        };
+        let resubmitted = poise::execute_modal_on_component_interaction(
+            ctx,
+            interaction,
+            Some(form.clone()),
+            Some(FORM_TIMEOUT),
+        )
+        .await?;
+        reply.delete(ctx).await?;
+        match resubmitted {
+            Some(resubmitted) => form = resubmitted,
+            None => return Ok(()),
+        }
+    };
+    event.reminders.offsets = reminder_offsets.unwrap_or_else(|| config.reminder_offsets.clone());
+    event.color = config.embed_color;
+    event.reminders.dm = dm_reminders.unwrap_or(true);
+    event.reminders.ping_role = ping_role.map(|role| role.id);
+    if let Some(duration) = duration.or(template.map(|template| template.duration)) {
+        event.duration = duration;
     }
-    while let Some(role) = extract_role(private_cannel.id, ctx.serenity_context(), None).await {
-        event.add_role(role);
-    }
-    let mut first_flavor = Some(());
-    while let Some(flavor) =
-        extract_flavor(private_cannel.id, ctx.serenity_context(), first_flavor).await
-    {
-        event.add_flavor(flavor);
-        first_flavor = None;
+    if draft.unwrap_or(false) {
+        event.state = EventState::Draft;
     }
-    let channel = match create_new_channel {
-        Some(_) => ctx
-            .guild_id()
-            .unwrap()
-            .create_channel(ctx.http(), CreateChannel::new(&event.title))
-            .await
-            .unwrap(),
-        None => ctx.guild_channel().await.unwrap(),
+    event.native_event = discord_event.unwrap_or(false);
+    event.own_thread = thread.unwrap_or(false);
+    event.own_voice = voice.unwrap_or(false);
+    event.voice_waitlist = voice_waitlist.unwrap_or(false);
+    event.auto_check_in = auto_check_in.unwrap_or(false);
+    let channel_id = if create_new_channel.unwrap_or(config.own_channel && !event.own_thread) {
+        let channel_id = create_event_channel(ctx.serenity_context(), &config, &event).await?;
+        event.own_channel = Some(channel_id);
+        channel_id
+    } else {
+        config.event_channel.unwrap_or(ctx.channel_id())
     };
-    let reply = format!("\nThe following event was created by {}:", ctx.author());
-    ctx.say(reply).await?;
-    let event_message = CreateMessage::new().add_embed(
-        CreateEmbed::new()
-            .title(&event.title)
-            .description(event.build_new_message())
-            .color(Color::PURPLE),
+    let reply = format!(
+        "\nThe following event was created by {} in {}.",
+        ctx.author(),
+        channel_id.mention()
     );
-    event.add_event_message(
-        channel
-            .id
-            .send_message(ctx.http(), event_message)
-            .await
-            .unwrap(),
-    );
-    if let Some(event_data) = ctx
-        .serenity_context()
-        .data
-        .write()
-        .await
-        .get_mut::<EventData>()
-    {
-        event_data.push(event)
+    ctx.say(reply).await?;
+    if let Some(recurrence) = recurrence {
+        store.save_series(&Series::new(&mut event, recurrence, channel_id))?;
     }
+    event.post_message(ctx.http(), channel_id).await?;
+    store.save_event(&event)?;
+    sync_scheduled_event(ctx.serenity_context(), &mut event).await;
+    open_thread(ctx.serenity_context(), &mut event).await;
     Ok(())
 }
 
-/// List all current events for this server
-#[poise::command(slash_command, prefix_command)]
-pub async fn list_events(ctx: Context<'_>) -> Result<(), Error> {
-    ctx.say("Thinking...").await?.delete(ctx).await?;
-    let mut data = ctx.serenity_context().data.write().await;
-    for event in data.get_mut::<EventData>().unwrap() {
-        if event.server_id() != ctx.guild_id().unwrap() {
-            continue;
+/// Edit an event
+#[poise::command(slash_command, guild_only)]
+#[allow(clippy::too_many_arguments)]
+pub async fn edit_event(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_event"] event: String,
+    title: Option<String>,
+    description: Option<String>,
+    #[description = "New start, e.g. 2026-11-03 19:30 or next friday 8pm"] date: Option<String>,
+    #[description = "Length of the event, e.g. 2h or 90m"] duration: Option<String>,
+    #[description = "All roles of the event, e.g. Tank:2, Healer:2, DPS:6"] roles: Option<String>,
+    #[description = "All flavors, e.g. Ranged:4, DPS/Melee:2+, or none"] flavors: Option<String>,
+) -> Result<(), Error> {
+    let store = event_store(ctx.serenity_context()).await;
+    let event = store
+        .event(event_id(&event)?)?
+        .ok_or(EventError::NotFound)?;
+    if !can_manage(ctx, &event).await {
+        return reply(
+            ctx,
+            "Only the organizers of the event or an admin can edit it.",
+        )
+        .await;
+    }
+    let zone = Clock::of(store.as_ref(), ctx.author().id, ctx.guild_id())?.zone;
+    let parsed = (|| {
+        Ok::<_, String>((
+            date.as_deref()
+                .map(|date| parse_date(date, zone, Utc::now()))
+                .transpose()?,
+            duration.as_deref().map(parse_duration).transpose()?,
+            roles.as_deref().map(parse_roles).transpose()?,
+            flavors
+                .as_deref()
+                .map(|spec| match spec.trim().eq_ignore_ascii_case("none") {
+                    true => Ok(Vec::new()),
+                    false => parse_flavors(spec),
+                })
+                .transpose()?,
+        ))
+    })();
+    let (date, duration, roles, flavors) = match parsed {
+        Ok(parsed) => parsed,
+        Err(err) => return reply(ctx, err).await,
+    };
+    let flavors = flavors.or_else(|| Some(retain_flavors(roles.as_ref()?, &event.needed_flavors)));
+    if let Err(err) = check_flavors(
+        roles.as_ref().unwrap_or(&event.needed_roles),
+        flavors.as_ref().unwrap_or(&event.needed_flavors),
+    ) {
+        return reply(ctx, err).await;
+    }
+
+    let moved = date.is_some();
+    let (mut event, changes) = store.update(event.id, |event| {
+        if let Some(title) = title {
+            event.title = title;
         }
-        let event_message = ctx
-            .channel_id()
-            .send_message(
-                ctx.http(),
-                CreateMessage::new().add_embed(
-                    CreateEmbed::new()
-                        .title(&event.title)
-                        .description(event.build_new_message())
-                        .color(Color::PURPLE),
-                ),
+        if let Some(description) = description {
+            event.description = description;
+        }
+        if let Some(date) = date {
+            event.date = date;
+        }
+        if let Some(duration) = duration {
+            event.duration = duration;
+        }
+        Ok((roles.is_some() || flavors.is_some()).then(|| {
+            event.update_roles(
+                roles.unwrap_or_else(|| event.needed_roles.clone()),
+                flavors.unwrap_or_else(|| event.needed_flavors.clone()),
             )
-            .await
-            .unwrap();
-        event.add_event_message(event_message);
+        }))
+    })?;
+    if moved {
+        store.reset_reminders(event.id)?;
+    }
+    publish(ctx.serenity_context(), &event).await?;
+    sync_scheduled_event(ctx.serenity_context(), &mut event).await;
+    if let Some(changes) = changes {
+        notify_roster_changes(ctx.serenity_context(), &event, &changes).await;
+        for removed in &changes.removed {
+            sync_thread_member(ctx.serenity_context(), &event, removed.id.id).await;
+        }
+    }
+    reply(ctx, "The event has been updated.").await
+}
+
+/// Cancel an event and let its participants know
+#[poise::command(slash_command, guild_only)]
+pub async fn cancel_event(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_event"] event: String,
+    #[description = "Told to everyone who signed up"] reason: Option<String>,
+) -> Result<(), Error> {
+    let store = event_store(ctx.serenity_context()).await;
+    let event = store
+        .event(event_id(&event)?)?
+        .ok_or(EventError::NotFound)?;
+    if !can_manage(ctx, &event).await {
+        return reply(
+            ctx,
+            "Only the organizers of the event or an admin can cancel it.",
+        )
+        .await;
+    }
+    let (mut event, ()) = store.update(event.id, |event| Ok(event.cancel(reason)?))?;
+    publish(ctx.serenity_context(), &event).await?;
+    sync_scheduled_event(ctx.serenity_context(), &mut event).await;
+    archive_thread(ctx.serenity_context(), &event).await;
+    notify_cancelled(ctx.serenity_context(), &event).await;
+    reply(ctx, "The event has been cancelled.").await
+}
+
+/// Open, lock, start or complete an event
+#[poise::command(slash_command, guild_only)]
+pub async fn event_state(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_event"] event: String,
+    state: EventState,
+) -> Result<(), Error> {
+    let store = event_store(ctx.serenity_context()).await;
+    let event = store
+        .event(event_id(&event)?)?
+        .ok_or(EventError::NotFound)?;
+    if !can_manage(ctx, &event).await {
+        return reply(
+            ctx,
+            "Only the organizers of the event or an admin can change its state.",
+        )
+        .await;
+    }
+    if state == EventState::Cancelled {
+        return reply(ctx, "Use /cancel_event to cancel an event.").await;
+    }
+    let (mut event, ()) = store.update(event.id, |event| Ok(event.set_state(state)?))?;
+    publish(ctx.serenity_context(), &event).await?;
+    sync_scheduled_event(ctx.serenity_context(), &mut event).await;
+    archive_thread(ctx.serenity_context(), &event).await;
+    if state == EventState::Completed {
+        store.save_attendance(&event.attendance())?;
+    }
+    reply(ctx, format!("The event is now {}.", state.as_str())).await
+}
+
+/// Delete an event and its posted messages
+#[poise::command(slash_command, guild_only)]
+pub async fn delete_event(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_event"] event: String,
+) -> Result<(), Error> {
+    let store = event_store(ctx.serenity_context()).await;
+    let mut event = store
+        .event(event_id(&event)?)?
+        .ok_or(EventError::NotFound)?;
+    if !can_manage(ctx, &event).await {
+        return reply(
+            ctx,
+            "Only the organizers of the event or an admin can delete it.",
+        )
+        .await;
+    }
+    event.delete_event_messages(ctx.http()).await;
+    delete_scheduled_event(ctx.serenity_context(), &mut event).await;
+    close_voice_channel(ctx.serenity_context(), &mut event).await?;
+    close_thread(ctx.serenity_context(), &event).await;
+    if let Some(channel_id) = event.own_channel {
+        let config = store.guild_config(event.server_id())?;
+        clean_up_channel(ctx.serenity_context(), &config, channel_id, &event.title).await?;
+    }
+    store.delete_event(event.id)?;
+    reply(ctx, "The event has been deleted.").await
+}
+
+/// List all current events for this server
+#[poise::command(slash_command, prefix_command, guild_only)]
+pub async fn list_events(ctx: Context<'_>) -> Result<(), Error> {
+    let store = event_store(ctx.serenity_context()).await;
+    let guild_id = ctx.guild_id().unwrap();
+    let channel_id = store
+        .guild_config(guild_id)?
+        .event_channel
+        .unwrap_or(ctx.channel_id());
+    reply(
+        ctx,
+        format!("Posting the events in {}.", channel_id.mention()),
+    )
+    .await?;
+    for mut event in store.events(guild_id)?.into_iter().filter(|ev| {
+        matches!(
+            ev.state(),
+            EventState::Open | EventState::Locked | EventState::Started
+        )
+    }) {
+        let message = event.post_message(ctx.http(), channel_id).await?;
+        store.update(event.id, |event| {
+            event.add_event_message(&message);
+            Ok(())
+        })?;
     }
     Ok(())
 }
 
 /// Signup for an event
-#[poise::command(slash_command, prefix_command)]
+#[poise::command(slash_command, prefix_command, guild_only)]
 pub async fn sign_up(ctx: Context<'_>) -> Result<(), Error> {
     ctx.say("Thinking...").await?.delete(ctx).await?;
-    let events_on_server = {
-        let event_data = ctx.serenity_context().data.read().await;
-        event_data
-            .get::<EventData>()
-            .unwrap()
-            .iter()
-            .filter(|ev| ev.server_id() == ctx.guild_id().unwrap())
-            .filter(|ev| !ev.contains_participant(ctx.author()))
-            .cloned()
-            .collect::<Vec<Event>>()
-    };
+    let store = event_store(ctx.serenity_context()).await;
+    let events_on_server = store
+        .events(ctx.guild_id().unwrap())?
+        .into_iter()
+        .filter(|ev| {
+            ev.state() == EventState::Open
+                && (!ev.contains_participant(ctx.author())
+                    || ev.declined_without_role(ctx.author()))
+        })
+        .collect::<Vec<Event>>();
     if events_on_server.is_empty() {
         ctx.author()
             .dm(
@@ -159,32 +464,28 @@ pub async fn sign_up(ctx: Context<'_>) -> Result<(), Error> {
                     },
                 )),
         )
-        .await
-        .unwrap();
-    let interaction = message
+        .await?;
+    let Some(interaction) = message
         .await_component_interaction(&ctx.serenity_context().shard)
         .timeout(Duration::from_secs(30))
         .await
-        .unwrap();
+    else {
+        return Ok(());
+    };
     let event_selection = match &interaction.data.kind {
         ComponentInteractionDataKind::StringSelect { values } => String::from(&values[0]),
-        _ => panic!("unexpected interaction data kind"),
+        _ => return Ok(()),
     };
-    message.delete(&ctx).await.unwrap();
+    message.delete(&ctx).await?;
     let selected_event = events_on_server
         .iter()
         .find(|ev| ev.id.to_string() == event_selection)
-        .unwrap();
+        .ok_or(EventError::NotFound)?;
     let possible_roles = events_on_server
         .iter()
         .find(|ev| ev.id.to_string() == event_selection)
-        .unwrap()
+        .ok_or(EventError::NotFound)?
         .roles();
-    let possible_flavors = events_on_server
-        .iter()
-        .find(|ev| ev.id.to_string() == event_selection)
-        .unwrap()
-        .flavors();
     let message = ctx
         .author()
         .dm(
@@ -196,95 +497,102 @@ pub async fn sign_up(ctx: Context<'_>) -> Result<(), Error> {
                     CreateSelectMenuKind::String {
                         options: possible_roles
                             .iter()
-                            .filter(|rl| !selected_event.is_role_full(rl))
-                            .map(|rl| CreateSelectMenuOption::new(rl, rl))
+                            .map(|rl| {
+                                CreateSelectMenuOption::new(selected_event.role_label(rl), rl)
+                            })
                             .collect(),
                     },
                 )),
         )
-        .await
-        .unwrap();
-    let interaction = message
+        .await?;
+    let Some(interaction) = message
         .await_component_interaction(&ctx.serenity_context().shard)
         .timeout(Duration::from_secs(30))
         .await
-        .unwrap();
+    else {
+        return Ok(());
+    };
     let role_selection = match &interaction.data.kind {
         ComponentInteractionDataKind::StringSelect { values } => String::from(&values[0]),
-        _ => panic!("unexpected interaction data kind"),
+        _ => return Ok(()),
     };
-    message.delete(&ctx).await.unwrap();
-    let message = ctx
-        .author()
-        .dm(
-            ctx.http(),
-            CreateMessage::new()
-                .content("Please Pick a Flavor for the event")
-                .select_menu(CreateSelectMenu::new(
-                    "role_select",
-                    CreateSelectMenuKind::String {
-                        options: possible_flavors
-                            .iter()
-                            .filter(|fl| !selected_event.is_flavor_full(fl))
-                            .map(|fl| CreateSelectMenuOption::new(fl, fl))
-                            .collect(),
-                    },
-                )),
-        )
-        .await
-        .unwrap();
-    let interaction = message
-        .await_component_interaction(&ctx.serenity_context().shard)
-        .timeout(Duration::from_secs(30))
-        .await
-        .unwrap();
-    let flavor_selection = match &interaction.data.kind {
-        ComponentInteractionDataKind::StringSelect { values } => String::from(&values[0]),
-        _ => String::from(""),
+    message.delete(&ctx).await?;
+    let possible_flavors = selected_event.flavors(&role_selection);
+    let flavor_selection = if possible_flavors.is_empty() {
+        String::new()
+    } else {
+        let message = ctx
+            .author()
+            .dm(
+                ctx.http(),
+                CreateMessage::new()
+                    .content("Please Pick a Flavor for the event")
+                    .select_menu(CreateSelectMenu::new(
+                        "role_select",
+                        CreateSelectMenuKind::String {
+                            options: possible_flavors
+                                .iter()
+                                .map(|fl| {
+                                    CreateSelectMenuOption::new(
+                                        selected_event.flavor_label(&role_selection, fl),
+                                        fl,
+                                    )
+                                })
+                                .collect(),
+                        },
+                    )),
+            )
+            .await?;
+        let Some(interaction) = message
+            .await_component_interaction(&ctx.serenity_context().shard)
+            .timeout(Duration::from_secs(30))
+            .await
+        else {
+            return Ok(());
+        };
+        let flavor_selection = match &interaction.data.kind {
+            ComponentInteractionDataKind::StringSelect { values } => String::from(&values[0]),
+            _ => String::from(""),
+        };
+        message.delete(&ctx).await?;
+        flavor_selection
+    };
+    let (event, placement) = store.update(event_id(&event_selection)?, |event| {
+        Ok(event.add_participant(
+            false,
+            ctx.author().clone(),
+            role_selection,
+            flavor_selection,
+            RsvpStatus::Accepted,
+        )?)
+    })?;
+    publish(ctx.serenity_context(), &event).await?;
+    sync_thread_member(ctx.serenity_context(), &event, ctx.author().id).await;
+    let content = match placement {
+        Placement::Roster => format!(
+            "Thank you for signing up for **{}** on <t:{}:F> ({})! We'll handle the rest.",
+            event.title,
+            event.date().timestamp(),
+            Clock::of(store.as_ref(), ctx.author().id, ctx.guild_id())?.format(event.date())
+        ),
+        Placement::Waitlist => String::from(WAITLIST_REPLY),
     };
-    message.delete(&ctx).await.unwrap();
     ctx.author()
-        .dm(
-            ctx.http(),
-            CreateMessage::new().content("Thank you for signing up! We'll handle the rest."),
-        )
-        .await
-        .unwrap();
-    if let Some(event_data) = ctx
-        .serenity_context()
-        .data
-        .write()
-        .await
-        .get_mut::<EventData>()
-    {
-        if let Some(event) = event_data
-            .iter_mut()
-            .find(|ev| ev.id.to_string() == event_selection)
-        {
-            event
-                .add_participant(ctx.author().clone(), role_selection, flavor_selection)
-                .unwrap();
-            event.update_event_messages(ctx.http()).await;
-        }
-    }
+        .dm(ctx.http(), CreateMessage::new().content(content))
+        .await?;
     Ok(())
 }
 
 /// Sign off from an event
-#[poise::command(slash_command, prefix_command)]
+#[poise::command(slash_command, prefix_command, guild_only)]
 pub async fn sign_off(ctx: Context<'_>) -> Result<(), Error> {
     ctx.say("Thinking...").await?.delete(ctx).await?;
-    let events_on_server = {
-        let event_data = ctx.serenity_context().data.read().await;
-        event_data
-            .get::<EventData>()
-            .unwrap()
-            .iter()
-            .filter(|ev| ev.server_id() == ctx.guild_id().unwrap())
-            .filter(|ev| ev.contains_participant(ctx.author()))
-            .cloned()
-            .collect::<Vec<Event>>()
-    };
+    let store = event_store(ctx.serenity_context()).await;
+    let events_on_server = store
+        .events(ctx.guild_id().unwrap())?
+        .into_iter()
+        .filter(|ev| ev.state() == EventState::Open && ev.contains_participant(ctx.author()))
+        .collect::<Vec<Event>>();
     if events_on_server.is_empty() {
         ctx.author()
             .dm(
@@ -312,33 +620,25 @@ pub async fn sign_off(ctx: Context<'_>) -> Result<(), Error> {
                 ))
                 .flags(MessageFlags::EPHEMERAL),
         )
-        .await
-        .unwrap();
-    let interaction = message
+        .await?;
+    let Some(interaction) = message
         .await_component_interaction(&ctx.serenity_context().shard)
         .timeout(Duration::from_secs(30))
         .await
-        .unwrap();
+    else {
+        return Ok(());
+    };
     let event_selection = match &interaction.data.kind {
         ComponentInteractionDataKind::StringSelect { values } => String::from(&values[0]),
-        _ => panic!("unexpected interaction data kind"),
+        _ => return Ok(()),
     };
-    message.delete(&ctx).await.unwrap();
-    if let Some(event_data) = ctx
-        .serenity_context()
-        .data
-        .write()
-        .await
-        .get_mut::<EventData>()
-    {
-        if let Some(event) = event_data
-            .iter_mut()
-            .find(|ev| ev.id.to_string() == event_selection)
-        {
-            event.remove_participant(ctx.author().clone()).unwrap();
-            event.update_event_messages(ctx.http()).await;
-        }
-    }
+    message.delete(&ctx).await?;
+    let (event, promoted) = store.update(event_id(&event_selection)?, |event| {
+        Ok(event.remove_participant(false, ctx.author().clone())?)
+    })?;
+    publish(ctx.serenity_context(), &event).await?;
+    sync_thread_member(ctx.serenity_context(), &event, ctx.author().id).await;
+    notify_promoted(ctx.serenity_context(), &event, &promoted).await;
     ctx.author()
         .dm(
             ctx.http(),
@@ -346,7 +646,6 @@ pub async fn sign_off(ctx: Context<'_>) -> Result<(), Error> {
                 .content("You have been removed from the event.")
                 .flags(MessageFlags::EPHEMERAL),
         )
-        .await
-        .unwrap();
+        .await?;
     Ok(())
 }
@@ -0,0 +1,80 @@
+use poise::serenity_prelude::CreateAttachment;
+use poise::CreateReply;
+
+use crate::commands::autocomplete::autocomplete_event;
+use crate::commands::{event_id, reply};
+use crate::storage::event_store;
+use crate::structs::client_structs::{Context, Error};
+use crate::structs::event::{Event, EventState};
+use crate::structs::event_error::EventError;
+use crate::util::ical::calendar;
+use crate::util::permissions::can_manage;
+
+#[derive(Clone, Copy, PartialEq, poise::ChoiceParameter)]
+pub enum ExportScope {
+    #[name = "One event"]
+    Event,
+    #[name = "My events"]
+    Mine,
+    #[name = "Whole server"]
+    Server,
+}
+
+/// Download events as an .ics file for your calendar
+#[poise::command(slash_command, guild_only)]
+pub async fn export_ics(
+    ctx: Context<'_>,
+    scope: ExportScope,
+    #[description = "Needed when exporting one event"]
+    #[autocomplete = "autocomplete_event"]
+    event: Option<String>,
+) -> Result<(), Error> {
+    let store = event_store(ctx.serenity_context()).await;
+    let guild_id = ctx.guild_id().unwrap();
+    let (events, filename) = match scope {
+        ExportScope::Event => {
+            let Some(event) = event else {
+                return reply(ctx, "Pick the event to export.").await;
+            };
+            let event = store
+                .event(event_id(&event)?)?
+                .filter(|event| event.server_id() == guild_id)
+                .ok_or(EventError::NotFound)?;
+            // Drafts are only visible to the people who can manage them.
+            if event.state() == EventState::Draft && !can_manage(ctx, &event).await {
+                return Err(EventError::NotFound.into());
+            }
+            (vec![event], "event.ics")
+        }
+        ExportScope::Mine => {
+            let events = store
+                .events(guild_id)?
+                .into_iter()
+                .filter(|ev| ev.attends(ctx.author().id))
+                .collect::<Vec<Event>>();
+            (events, "my_events.ics")
+        }
+        ExportScope::Server => {
+            let events = store
+                .events(guild_id)?
+                .into_iter()
+                .filter(|ev| ev.state() != EventState::Draft)
+                .collect::<Vec<Event>>();
+            (events, "events.ics")
+        }
+    };
+    if events.is_empty() {
+        return reply(ctx, "There are no events to export.").await;
+    }
+    ctx.send(
+        CreateReply::default()
+            .content(format!(
+                "Here are {} event(s) for your calendar.",
+                events.len()
+            ))
+            .attachment(CreateAttachment::bytes(calendar(&events), filename))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
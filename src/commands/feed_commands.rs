@@ -0,0 +1,81 @@
+use poise::serenity_prelude::CreateMessage;
+
+use crate::commands::reply;
+use crate::feed::feed_url;
+use crate::storage::event_store;
+use crate::structs::client_structs::{Context, Error};
+use crate::structs::feed::FeedOwner;
+use crate::util::permissions::is_admin;
+
+/// Subscribe to events in your calendar app
+#[poise::command(slash_command, subcommands("feed_personal", "feed_server"))]
+pub async fn feed(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Looks up the token of the feed, handing out a new one if there is none
+/// yet or `rotate` is set.
+async fn feed_token(ctx: Context<'_>, owner: FeedOwner, rotate: bool) -> Result<String, Error> {
+    let store = event_store(ctx.serenity_context()).await;
+    Ok(match store.feed_token(owner)? {
+        Some(token) if !rotate => token,
+        _ => store.rotate_feed_token(owner)?,
+    })
+}
+
+/// Feed URLs are secrets, so they only ever go to DMs.
+async fn send_privately(ctx: Context<'_>, content: String) -> Result<(), Error> {
+    if ctx.guild_id().is_none() {
+        return reply(ctx, content).await;
+    }
+    ctx.author()
+        .dm(ctx.http(), CreateMessage::new().content(content))
+        .await?;
+    reply(ctx, "I sent you the link in a DM.").await
+}
+
+/// Get the feed of the events you signed up for
+#[poise::command(slash_command, rename = "personal")]
+pub async fn feed_personal(
+    ctx: Context<'_>,
+    #[description = "Replace the link, e.g. because it leaked"] rotate: Option<bool>,
+) -> Result<(), Error> {
+    let rotate = rotate.unwrap_or(false);
+    let token = feed_token(ctx, FeedOwner::User(ctx.author().id), rotate).await?;
+    send_privately(
+        ctx,
+        format!(
+            "{}Add this URL to your calendar app to see the events you signed up for:\n<{}>\nKeep it to yourself, anyone with the link can see your events.",
+            if rotate { "The old link no longer works. " } else { "" },
+            feed_url(&token)
+        ),
+    )
+    .await
+}
+
+/// Get the feed of every event of this server
+#[poise::command(slash_command, guild_only, rename = "server")]
+pub async fn feed_server(
+    ctx: Context<'_>,
+    #[description = "Replace the link for everyone (admins only)"] rotate: Option<bool>,
+) -> Result<(), Error> {
+    let rotate = rotate.unwrap_or(false);
+    if rotate && !is_admin(ctx).await {
+        return reply(ctx, "Only server admins can replace the server feed.").await;
+    }
+    let owner = FeedOwner::Guild(ctx.guild_id().unwrap());
+    let token = feed_token(ctx, owner, rotate).await?;
+    send_privately(
+        ctx,
+        format!(
+            "{}Add this URL to your calendar app to see every event of the server:\n<{}>",
+            if rotate {
+                "The old link no longer works. "
+            } else {
+                ""
+            },
+            feed_url(&token)
+        ),
+    )
+    .await
+}
@@ -1 +1,75 @@
+pub mod attendance_commands;
+pub mod autocomplete;
+pub mod config_commands;
+pub mod event_buttons;
 pub mod event_commands;
+pub mod export_commands;
+#[cfg(feature = "ics-server")]
+pub mod feed_commands;
+pub mod organizer_commands;
+pub mod roster_commands;
+pub mod series_commands;
+pub mod template_commands;
+pub mod timezone_commands;
+
+use poise::{CreateReply, FrameworkError};
+use uuid::Uuid;
+
+use crate::storage::event_store;
+use crate::structs::client_structs::{Context, Data, Error};
+use crate::structs::event::Event;
+use crate::structs::event_error::EventError;
+use crate::util::permissions::can_manage;
+
+/// Replies with a message only the author can see.
+async fn reply(ctx: Context<'_>, content: impl Into<String>) -> Result<(), Error> {
+    ctx.send(CreateReply::default().content(content).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Reads the id of an event picked from the suggestions. Anything else, e.g.
+/// text typed without picking a suggestion, names no event.
+fn event_id(selection: &str) -> Result<Uuid, EventError> {
+    Uuid::parse_str(selection.trim()).map_err(|_| EventError::NotFound)
+}
+
+/// Loads the picked event if the author may manage it, and otherwise tells
+/// them that only organizers and admins can `action`.
+async fn load_managed(ctx: Context<'_>, event: &str, action: &str) -> Result<Option<Event>, Error> {
+    let store = event_store(ctx.serenity_context()).await;
+    let event = store.event(event_id(event)?)?.ok_or(EventError::NotFound)?;
+    if !can_manage(ctx, &event).await {
+        reply(
+            ctx,
+            format!("Only the organizers of the event or an admin can {action}."),
+        )
+        .await?;
+        return Ok(None);
+    }
+    Ok(Some(event))
+}
+
+/// Answers failed commands. Refused event changes are explained to the
+/// author, anything unexpected is logged and gets a generic apology.
+pub async fn on_error(error: FrameworkError<'_, Data, Error>) {
+    let FrameworkError::Command { error, ctx, .. } = error else {
+        if let Err(err) = poise::builtins::on_error(error).await {
+            eprintln!("Could not handle an error: {err}");
+        }
+        return;
+    };
+    let content = match error.downcast_ref::<EventError>() {
+        Some(err) => err.to_string(),
+        None => {
+            eprintln!("/{} failed: {error}", ctx.command().qualified_name);
+            String::from("Something went wrong. Please try again later.")
+        }
+    };
+    if let Err(err) = reply(ctx, content).await {
+        eprintln!(
+            "Could not report an error of /{}: {err}",
+            ctx.command().qualified_name
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use poise::serenity_prelude::{self as serenity, Mentionable};
+
+use crate::commands::autocomplete::autocomplete_event;
+use crate::commands::{event_id, load_managed, reply};
+use crate::storage::event_store;
+use crate::structs::client_structs::{Context, Error};
+use crate::structs::event_error::EventError;
+
+/// Manage who besides the creator may manage an event
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("organizer_add", "organizer_remove", "organizer_list")
+)]
+pub async fn organizer(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Let a member manage an event
+#[poise::command(slash_command, rename = "add")]
+pub async fn organizer_add(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_event"] event: String,
+    member: serenity::User,
+) -> Result<(), Error> {
+    let Some(event) = load_managed(ctx, &event, "change its organizers").await? else {
+        return Ok(());
+    };
+    if event.is_organizer(member.id) {
+        return reply(
+            ctx,
+            format!("{} already organizes this event.", member.mention()),
+        )
+        .await;
+    }
+    event_store(ctx.serenity_context())
+        .await
+        .update(event.id, |event| {
+            if !event.is_organizer(member.id) {
+                event.co_organizers.push(member.id);
+            }
+            Ok(())
+        })?;
+    reply(
+        ctx,
+        format!("{} now co-organizes **{}**.", member.mention(), event.title),
+    )
+    .await
+}
+
+/// Stop a member from managing an event
+#[poise::command(slash_command, rename = "remove")]
+pub async fn organizer_remove(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_event"] event: String,
+    member: serenity::User,
+) -> Result<(), Error> {
+    let Some(event) = load_managed(ctx, &event, "change its organizers").await? else {
+        return Ok(());
+    };
+    if member.id == event.creator.id {
+        return reply(ctx, "The creator of an event always organizes it.").await;
+    }
+    let (event, removed) = event_store(ctx.serenity_context())
+        .await
+        .update(event.id, |event| {
+            let before = event.co_organizers.len();
+            event.co_organizers.retain(|id| *id != member.id);
+            Ok(event.co_organizers.len() < before)
+        })?;
+    if !removed {
+        return reply(
+            ctx,
+            format!("{} does not co-organize this event.", member.mention()),
+        )
+        .await;
+    }
+    reply(
+        ctx,
+        format!(
+            "{} no longer co-organizes **{}**.",
+            member.mention(),
+            event.title
+        ),
+    )
+    .await
+}
+
+/// Show who organizes an event
+#[poise::command(slash_command, rename = "list")]
+pub async fn organizer_list(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_event"] event: String,
+) -> Result<(), Error> {
+    let store = event_store(ctx.serenity_context()).await;
+    let event = store
+        .event(event_id(&event)?)?
+        .filter(|event| ctx.guild_id() == Some(event.server_id()))
+        .ok_or(EventError::NotFound)?;
+    let organizers = std::iter::once(event.creator.id)
+        .chain(event.co_organizers.iter().copied())
+        .map(|id| id.mention().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    reply(
+        ctx,
+        format!("**{}** is organized by {}.", event.title, organizers),
+    )
+    .await
+}
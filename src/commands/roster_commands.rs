@@ -0,0 +1,106 @@
+use poise::serenity_prelude::{self as serenity, Mentionable};
+
+use crate::commands::autocomplete::autocomplete_event;
+use crate::commands::{load_managed, reply};
+use crate::storage::event_store;
+use crate::structs::client_structs::{Context, Error};
+use crate::structs::event::{Placement, RsvpStatus};
+use crate::structs::event_error::EventError;
+use crate::util::event::{notify_promoted, publish};
+use crate::util::thread::sync_thread_member;
+
+/// Sign members up or off for them, also while the roster is locked
+#[poise::command(slash_command, guild_only, subcommands("roster_add", "roster_remove"))]
+pub async fn roster(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Sign a member up for an event
+#[poise::command(slash_command, rename = "add")]
+pub async fn roster_add(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_event"] event: String,
+    member: serenity::User,
+    role: String,
+    flavor: Option<String>,
+) -> Result<(), Error> {
+    let Some(event) = load_managed(ctx, &event, "change its roster").await? else {
+        return Ok(());
+    };
+    let signed_up = event_store(ctx.serenity_context())
+        .await
+        .update(event.id, |event| {
+            Ok(event.add_participant(
+                true,
+                member.clone(),
+                role,
+                flavor.unwrap_or_default(),
+                RsvpStatus::Accepted,
+            )?)
+        });
+    // The stock message speaks to the member, not to the organizer.
+    let (event, placement) = match signed_up {
+        Err(err) if matches!(err.downcast_ref(), Some(EventError::AlreadySignedUp)) => {
+            return reply(
+                ctx,
+                format!("{} is already signed up for this event.", member.mention()),
+            )
+            .await;
+        }
+        signed_up => signed_up?,
+    };
+    publish(ctx.serenity_context(), &event).await?;
+    sync_thread_member(ctx.serenity_context(), &event, member.id).await;
+    let placed = match placement {
+        Placement::Roster => "signed up for",
+        Placement::Waitlist => "put on the waitlist of",
+    };
+    reply(
+        ctx,
+        format!(
+            "{} has been {placed} **{}**.",
+            member.mention(),
+            event.title
+        ),
+    )
+    .await
+}
+
+/// Sign a member off from an event
+#[poise::command(slash_command, rename = "remove")]
+pub async fn roster_remove(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_event"] event: String,
+    member: serenity::User,
+) -> Result<(), Error> {
+    let Some(event) = load_managed(ctx, &event, "change its roster").await? else {
+        return Ok(());
+    };
+    let signed_off = event_store(ctx.serenity_context())
+        .await
+        .update(event.id, |event| {
+            Ok(event.remove_participant(true, member.clone())?)
+        });
+    let (event, promoted) = match signed_off {
+        Err(err) if matches!(err.downcast_ref(), Some(EventError::NotSignedUp)) => {
+            return reply(
+                ctx,
+                format!("{} is not signed up for this event.", member.mention()),
+            )
+            .await;
+        }
+        signed_off => signed_off?,
+    };
+    publish(ctx.serenity_context(), &event).await?;
+    sync_thread_member(ctx.serenity_context(), &event, member.id).await;
+    notify_promoted(ctx.serenity_context(), &event, &promoted).await;
+    reply(
+        ctx,
+        format!(
+            "{} has been signed off from **{}**.",
+            member.mention(),
+            event.title
+        ),
+    )
+    .await
+}
@@ -0,0 +1,218 @@
+use chrono::Utc;
+
+use crate::commands::autocomplete::autocomplete_series_event;
+use crate::commands::{event_id, reply};
+use crate::storage::event_store;
+use crate::structs::client_structs::{Context, Error};
+use crate::structs::event::{Event, EventState};
+use crate::structs::event_error::EventError;
+use crate::structs::series::Series;
+use crate::util::channels::clean_up_channel;
+use crate::util::event::{notify_cancelled, publish};
+use crate::util::event_parser::{
+    check_flavors, parse_flavors, parse_roles, parse_time, retain_flavors, with_local_time,
+};
+use crate::util::permissions::can_manage;
+use crate::util::scheduled_event::sync_scheduled_event;
+use crate::util::thread::archive_thread;
+use crate::util::timezone::Clock;
+
+#[derive(Clone, Copy, PartialEq, poise::ChoiceParameter)]
+pub enum Scope {
+    #[name = "This occurrence"]
+    Occurrence,
+    #[name = "Whole series"]
+    Series,
+}
+
+/// Manage recurring events
+#[poise::command(slash_command, guild_only, subcommands("series_edit", "series_cancel"))]
+pub async fn series(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Loads the picked occurrence and its series, if the author may manage them.
+async fn load_series(
+    ctx: Context<'_>,
+    event: &str,
+) -> Result<Option<(Event, Series, Vec<Event>)>, Error> {
+    let store = event_store(ctx.serenity_context()).await;
+    let event = store.event(event_id(event)?)?.ok_or(EventError::NotFound)?;
+    let Some(series) = event
+        .series
+        .as_ref()
+        .map(|occ| store.series(occ.series_id))
+        .transpose()?
+        .flatten()
+    else {
+        reply(ctx, "This event is not part of a series.").await?;
+        return Ok(None);
+    };
+    if !can_manage(ctx, &series.template).await {
+        reply(
+            ctx,
+            "Only the organizers of the series or an admin can manage it.",
+        )
+        .await?;
+        return Ok(None);
+    }
+    let upcoming = store
+        .events(event.server_id())?
+        .into_iter()
+        .filter(|ev| ev.date > Utc::now())
+        .filter(|ev| {
+            ev.series
+                .as_ref()
+                .is_some_and(|occ| occ.series_id == series.id)
+        })
+        .collect();
+    Ok(Some((event, series, upcoming)))
+}
+
+/// Edit one occurrence or every upcoming occurrence of a series
+#[poise::command(slash_command, rename = "edit")]
+#[allow(clippy::too_many_arguments)]
+pub async fn series_edit(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_series_event"] event: String,
+    scope: Scope,
+    title: Option<String>,
+    description: Option<String>,
+    #[description = "Start time as HH:MM"] time: Option<String>,
+    #[description = "e.g. Tank:2, Healer:2, DPS:6"] roles: Option<String>,
+    #[description = "e.g. Ranged:4, DPS/Melee:2+"] flavors: Option<String>,
+) -> Result<(), Error> {
+    let Some((event, mut series, upcoming)) = load_series(ctx, &event).await? else {
+        return Ok(());
+    };
+    let parsed = (|| {
+        Ok::<_, String>((
+            time.as_deref().map(parse_time).transpose()?,
+            roles.as_deref().map(parse_roles).transpose()?,
+            flavors.as_deref().map(parse_flavors).transpose()?,
+        ))
+    })();
+    let (time, roles, flavors) = match parsed {
+        Ok(parsed) => parsed,
+        Err(err) => return reply(ctx, err).await,
+    };
+    if scope == Scope::Occurrence && (roles.is_some() || flavors.is_some()) {
+        return reply(
+            ctx,
+            "Roles and flavors can only be changed for the whole series.",
+        )
+        .await;
+    }
+    let template = &series.template;
+    let flavors =
+        flavors.or_else(|| Some(retain_flavors(roles.as_ref()?, &template.needed_flavors)));
+    if let Err(err) = check_flavors(
+        roles.as_ref().unwrap_or(&template.needed_roles),
+        flavors.as_ref().unwrap_or(&template.needed_flavors),
+    ) {
+        return reply(ctx, err).await;
+    }
+
+    let store = event_store(ctx.serenity_context()).await;
+    let zone = Clock::of(store.as_ref(), ctx.author().id, ctx.guild_id())?.zone;
+    let targets = match scope {
+        Scope::Occurrence => vec![event],
+        Scope::Series => upcoming,
+    };
+    let apply = |target: &mut Event| -> Result<(), String> {
+        if let Some(title) = &title {
+            target.title = title.clone();
+        }
+        if let Some(description) = &description {
+            target.description = description.clone();
+        }
+        if let Some(time) = time {
+            target.date = with_local_time(target.date, time, zone)?;
+        }
+        // Occurrences people already signed up for keep their roster.
+        if target.participants.is_empty() {
+            if let Some(roles) = &roles {
+                target.needed_roles = roles.clone();
+            }
+            if let Some(flavors) = &flavors {
+                target.needed_flavors = flavors.clone();
+            }
+        }
+        Ok(())
+    };
+    // Check every target up front, so a bad time doesn't leave the series half edited.
+    for target in &targets {
+        if let Err(err) = apply(&mut target.clone()) {
+            return reply(ctx, err).await;
+        }
+    }
+    if scope == Scope::Series {
+        if let Err(err) = apply(&mut series.template) {
+            return reply(ctx, err).await;
+        }
+        store.save_series(&series)?;
+    }
+    for target in targets {
+        let (mut target, _) = store.update(target.id, |target| Ok(apply(target)?))?;
+        if time.is_some() {
+            store.reset_reminders(target.id)?;
+        }
+        publish(ctx.serenity_context(), &target).await?;
+        sync_scheduled_event(ctx.serenity_context(), &mut target).await;
+    }
+    match scope {
+        Scope::Occurrence => reply(ctx, "The occurrence has been updated.").await,
+        Scope::Series if roles.is_some() || flavors.is_some() => {
+            reply(
+                ctx,
+                "The series has been updated. New roles and flavors apply to occurrences nobody has signed up for yet.",
+            )
+            .await
+        }
+        Scope::Series => reply(ctx, "The series has been updated.").await,
+    }
+}
+
+/// Cancel one occurrence or every upcoming occurrence of a series
+#[poise::command(slash_command, rename = "cancel")]
+pub async fn series_cancel(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_series_event"] event: String,
+    scope: Scope,
+) -> Result<(), Error> {
+    let Some((event, series, upcoming)) = load_series(ctx, &event).await? else {
+        return Ok(());
+    };
+    let store = event_store(ctx.serenity_context()).await;
+    // Occurrences stay as cancelled events, like ones cancelled with
+    // /cancel_event; only the series itself goes away.
+    let targets = match scope {
+        Scope::Occurrence => vec![event],
+        Scope::Series => upcoming
+            .into_iter()
+            .filter(|ev| !matches!(ev.state(), EventState::Completed | EventState::Cancelled))
+            .collect(),
+    };
+    for target in targets {
+        let (mut target, ()) = store.update(target.id, |event| Ok(event.cancel(None)?))?;
+        publish(ctx.serenity_context(), &target).await?;
+        sync_scheduled_event(ctx.serenity_context(), &mut target).await;
+        archive_thread(ctx.serenity_context(), &target).await;
+        notify_cancelled(ctx.serenity_context(), &target).await;
+    }
+    if scope == Scope::Occurrence {
+        return reply(ctx, "The occurrence has been cancelled.").await;
+    }
+    if let Some(channel_id) = series.own_channel {
+        let config = store.guild_config(series.template.server_id())?;
+        clean_up_channel(
+            ctx.serenity_context(),
+            &config,
+            channel_id,
+            &series.template.title,
+        )
+        .await?;
+    }
+    store.delete_series(series.id)?;
+    reply(ctx, "The series has been cancelled.").await
+}
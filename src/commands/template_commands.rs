@@ -0,0 +1,109 @@
+use crate::commands::autocomplete::{autocomplete_event, autocomplete_template};
+use crate::commands::{event_id, reply};
+use crate::storage::event_store;
+use crate::structs::client_structs::{Context, Error};
+use crate::structs::event_error::EventError;
+use crate::structs::template::EventTemplate;
+use crate::util::event_parser::{format_flavors, format_roles};
+use crate::util::permissions::is_organizer;
+
+/// Manage the templates new events can start from
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("template_save", "template_list", "template_delete")
+)]
+pub async fn template(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Save the roles, flavors, description and duration of an event as a template
+#[poise::command(slash_command, rename = "save")]
+pub async fn template_save(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_event"] event: String,
+    #[description = "Saving under an existing name replaces that template"]
+    #[max_length = 100]
+    name: String,
+) -> Result<(), Error> {
+    let store = event_store(ctx.serenity_context()).await;
+    let guild_id = ctx.guild_id().unwrap();
+    if !is_organizer(ctx, &store.guild_config(guild_id)?).await {
+        return reply(
+            ctx,
+            "Only members with the event organizer role or admins can save templates.",
+        )
+        .await;
+    }
+    let event = store
+        .event(event_id(&event)?)?
+        .filter(|event| event.server_id() == guild_id)
+        .ok_or(EventError::NotFound)?;
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return reply(ctx, "The template needs a name.").await;
+    }
+    let replaced = store.template(guild_id, &name)?.is_some();
+    store.save_template(&EventTemplate::from_event(guild_id, name.clone(), &event))?;
+    reply(
+        ctx,
+        if replaced {
+            format!("The template `{name}` has been replaced.")
+        } else {
+            format!("The template `{name}` has been saved.")
+        },
+    )
+    .await
+}
+
+/// Show the templates of this server
+#[poise::command(slash_command, rename = "list")]
+pub async fn template_list(ctx: Context<'_>) -> Result<(), Error> {
+    let templates = event_store(ctx.serenity_context())
+        .await
+        .templates(ctx.guild_id().unwrap())?;
+    if templates.is_empty() {
+        return reply(
+            ctx,
+            "This server has no templates yet. Save one with /template save.",
+        )
+        .await;
+    }
+    let lines = templates
+        .iter()
+        .map(|template| {
+            let mut line = format!(
+                "**{}**: {} ({} min)",
+                template.name,
+                format_roles(&template.roles),
+                template.duration.num_minutes()
+            );
+            if !template.flavors.is_empty() {
+                line += &format!(", flavors {}", format_flavors(&template.flavors));
+            }
+            line
+        })
+        .collect::<Vec<String>>();
+    reply(ctx, lines.join("\n")).await
+}
+
+/// Delete a template of this server
+#[poise::command(slash_command, rename = "delete")]
+pub async fn template_delete(
+    ctx: Context<'_>,
+    #[autocomplete = "autocomplete_template"] name: String,
+) -> Result<(), Error> {
+    let store = event_store(ctx.serenity_context()).await;
+    let guild_id = ctx.guild_id().unwrap();
+    if !is_organizer(ctx, &store.guild_config(guild_id)?).await {
+        return reply(
+            ctx,
+            "Only members with the event organizer role or admins can delete templates.",
+        )
+        .await;
+    }
+    if !store.delete_template(guild_id, &name)? {
+        return reply(ctx, format!("There is no template called `{name}`.")).await;
+    }
+    reply(ctx, format!("The template `{name}` has been deleted.")).await
+}
@@ -0,0 +1,71 @@
+use chrono::Utc;
+use chrono_tz::Tz;
+
+use crate::commands::autocomplete::autocomplete_timezone;
+use crate::commands::reply;
+use crate::storage::event_store;
+use crate::structs::client_structs::{Context, Error};
+use crate::util::timezone::Clock;
+
+/// Manage the timezone your dates are read and shown in
+#[poise::command(
+    slash_command,
+    subcommands("timezone_set", "timezone_show", "timezone_clear")
+)]
+pub async fn timezone(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Set your timezone
+#[poise::command(slash_command, rename = "set")]
+pub async fn timezone_set(
+    ctx: Context<'_>,
+    #[description = "IANA timezone, e.g. Europe/Berlin"]
+    #[autocomplete = "autocomplete_timezone"]
+    zone: String,
+) -> Result<(), Error> {
+    let Ok(zone) = Tz::from_str_insensitive(zone.trim()) else {
+        return reply(
+            ctx,
+            format!("`{zone}` is not a known timezone. Use a name like `Europe/Berlin`."),
+        )
+        .await;
+    };
+    let store = event_store(ctx.serenity_context()).await;
+    store.set_user_timezone(ctx.author().id, Some(zone))?;
+    let clock = Clock::of(store.as_ref(), ctx.author().id, ctx.guild_id())?;
+    reply(
+        ctx,
+        format!(
+            "Your timezone is now {}. It is {} there.",
+            zone.name(),
+            clock.format(Utc::now())
+        ),
+    )
+    .await
+}
+
+/// Show your timezone
+#[poise::command(slash_command, rename = "show")]
+pub async fn timezone_show(ctx: Context<'_>) -> Result<(), Error> {
+    let store = event_store(ctx.serenity_context()).await;
+    let content = match store.user_timezone(ctx.author().id)? {
+        Some(zone) => format!("Your timezone is {}.", zone.name()),
+        None => format!(
+            "You have not set a timezone, so {} is used.",
+            Clock::of(store.as_ref(), ctx.author().id, ctx.guild_id())?
+                .zone
+                .name()
+        ),
+    };
+    reply(ctx, content).await
+}
+
+/// Go back to the default timezone
+#[poise::command(slash_command, rename = "clear")]
+pub async fn timezone_clear(ctx: Context<'_>) -> Result<(), Error> {
+    event_store(ctx.serenity_context())
+        .await
+        .set_user_timezone(ctx.author().id, None)?;
+    reply(ctx, "Your timezone has been cleared.").await
+}
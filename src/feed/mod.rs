@@ -0,0 +1,69 @@
+use std::env;
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+
+use crate::storage::EventStore;
+use crate::structs::client_structs::Error;
+use crate::structs::event::{Event, EventState};
+use crate::structs::feed::FeedOwner;
+use crate::util::ical::calendar;
+
+/// Address the feed server listens on, taken from `FEED_ADDRESS`.
+pub fn address() -> String {
+    env::var("FEED_ADDRESS").unwrap_or_else(|_| String::from("127.0.0.1:8080"))
+}
+
+/// URL to subscribe to the feed opened by `token`. `FEED_URL` sets the public
+/// base URL when the server sits behind a proxy.
+pub fn feed_url(token: &str) -> String {
+    let base = env::var("FEED_URL").unwrap_or_else(|_| format!("http://{}", address()));
+    format!("{}/calendar/{token}.ics", base.trim_end_matches('/'))
+}
+
+/// Serves the calendar feeds until the process exits.
+pub async fn serve(store: Arc<dyn EventStore>) -> Result<(), Error> {
+    let app = Router::new()
+        .route("/calendar/{token}", get(feed))
+        .with_state(store);
+    let listener = tokio::net::TcpListener::bind(address()).await?;
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+async fn feed(State(store): State<Arc<dyn EventStore>>, Path(token): Path<String>) -> Response {
+    match feed_events(store.as_ref(), token.trim_end_matches(".ics")) {
+        Ok(Some(events)) => (
+            [(header::CONTENT_TYPE, "text/calendar; charset=utf-8")],
+            calendar(&events),
+        )
+            .into_response(),
+        Ok(None) => StatusCode::NOT_FOUND.into_response(),
+        Err(err) => {
+            eprintln!("Could not serve a calendar feed: {err}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// Events listed by the feed the token opens, or `None` for unknown tokens.
+fn feed_events(store: &dyn EventStore, token: &str) -> Result<Option<Vec<Event>>, Error> {
+    let events = match store.feed_owner(token)? {
+        Some(FeedOwner::User(user_id)) => store
+            .all_events()?
+            .into_iter()
+            .filter(|ev| ev.attends(user_id))
+            .collect(),
+        Some(FeedOwner::Guild(guild_id)) => store
+            .events(guild_id)?
+            .into_iter()
+            .filter(|ev| ev.state() != EventState::Draft)
+            .collect(),
+        None => return Ok(None),
+    };
+    Ok(Some(events))
+}
@@ -1,26 +1,86 @@
 pub mod commands;
+#[cfg(feature = "ics-server")]
+pub mod feed;
+pub mod scheduler;
+pub mod storage;
 pub mod structs;
 pub mod util;
 
-use commands::event_commands::{create_event, list_events, sign_off, sign_up};
+use commands::attendance_commands::attendance;
+use commands::config_commands::config;
+use commands::event_buttons::handle_event_button;
+use commands::event_commands::{
+    cancel_event, create_event, delete_event, edit_event, event_state, list_events, sign_off,
+    sign_up,
+};
+use commands::export_commands::export_ics;
+#[cfg(feature = "ics-server")]
+use commands::feed_commands::feed;
+use commands::on_error;
+use commands::organizer_commands::organizer;
+use commands::roster_commands::roster;
+use commands::series_commands::series;
+use commands::template_commands::template;
+use commands::timezone_commands::timezone;
 use poise::serenity_prelude::{self as serenity};
 use poise::{self};
 use std::env;
-use structs::client_structs::{Data, EventData};
+use std::sync::Arc;
+use storage::sqlite::SqliteEventStore;
+use storage::EventStore;
+use structs::client_structs::{Data, Error, EventData};
+use util::event::refresh_event_messages;
+use util::scheduled_event::{offer_sign_up, unlink_scheduled_event};
 
 #[tokio::main]
 async fn main() {
     let token = env::var("DISCORD_TOKEN").unwrap();
+    let database = env::var("EVENT_DATABASE").unwrap_or_else(|_| String::from("events.db"));
+    let store: Arc<dyn EventStore> = Arc::new(SqliteEventStore::open(database).unwrap());
     let intents = serenity::GatewayIntents::non_privileged();
 
+    #[allow(unused_mut)]
+    let mut commands = vec![
+        create_event(),
+        list_events(),
+        sign_up(),
+        sign_off(),
+        edit_event(),
+        cancel_event(),
+        delete_event(),
+        event_state(),
+        series(),
+        timezone(),
+        config(),
+        organizer(),
+        roster(),
+        template(),
+        export_ics(),
+        attendance(),
+    ];
+    #[cfg(feature = "ics-server")]
+    {
+        commands.push(feed());
+        let store = store.clone();
+        tokio::spawn(async move {
+            if let Err(err) = feed::serve(store).await {
+                eprintln!("The calendar feed server stopped: {err}");
+            }
+        });
+    }
+
     let framework = poise::Framework::builder()
         .options(poise::FrameworkOptions {
-            commands: vec![create_event(), list_events(), sign_up(), sign_off()],
+            commands,
+            event_handler: |ctx, event, _framework, _data| Box::pin(event_handler(ctx, event)),
+            on_error: |error| Box::pin(on_error(error)),
             ..Default::default()
         })
         .setup(|ctx, _ready, framework| {
             Box::pin(async move {
                 poise::builtins::register_globally(ctx, &framework.options().commands).await?;
+                tokio::spawn(refresh_event_messages(ctx.clone()));
+                tokio::spawn(scheduler::run(ctx.clone()));
                 Ok(Data {})
             })
         })
@@ -32,7 +92,30 @@ async fn main() {
         .unwrap();
     {
         let client = &mut client;
-        client.data.write().await.insert::<EventData>(Vec::new());
+        let mut data = client.data.write().await;
+        data.insert::<EventData>(store);
     }
     client.start().await.unwrap();
 }
+
+async fn event_handler(ctx: &serenity::Context, event: &serenity::FullEvent) -> Result<(), Error> {
+    match event {
+        serenity::FullEvent::InteractionCreate {
+            interaction: serenity::Interaction::Component(interaction),
+        } => handle_event_button(ctx, interaction).await?,
+        serenity::FullEvent::GuildScheduledEventUserAdd { subscribed } => {
+            offer_sign_up(
+                ctx,
+                subscribed.guild_id,
+                subscribed.scheduled_event_id,
+                subscribed.user_id,
+            )
+            .await?
+        }
+        serenity::FullEvent::GuildScheduledEventDelete { event } => {
+            unlink_scheduled_event(ctx, event.guild_id, event.id).await?
+        }
+        _ => {}
+    }
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use chrono::Utc;
+use poise::serenity_prelude::{ChannelId, Context, UserId};
+
+use crate::storage::event_store;
+use crate::structs::client_structs::Error;
+use crate::structs::event::{EventState, CHECK_IN_LEAD};
+use crate::util::event::publish;
+
+/// Checks in participants of events with auto check-in who are connected to
+/// voice during the check-in window: to the voice channel of the event if it
+/// has one, otherwise to any voice channel of the server.
+pub async fn check_in_from_voice(ctx: &Context) -> Result<(), Error> {
+    let store = event_store(ctx).await;
+    let now = Utc::now();
+    for event in store.all_events()? {
+        let window = event.date() - CHECK_IN_LEAD <= now && now < event.end();
+        let scheduled = matches!(
+            event.state(),
+            EventState::Open | EventState::Locked | EventState::Started
+        );
+        if !event.auto_check_in || !window || !scheduled {
+            continue;
+        }
+        let connected = match ctx.cache.guild(event.server_id()) {
+            Some(guild) => guild
+                .voice_states
+                .values()
+                .filter_map(|state| Some((state.user_id, state.channel_id?)))
+                .collect::<Vec<(UserId, ChannelId)>>(),
+            None => continue,
+        };
+        let present = connected
+            .into_iter()
+            .filter(|(_, channel)| event.voice.is_none_or(|voice| voice == *channel))
+            .map(|(user_id, _)| user_id)
+            .collect::<Vec<UserId>>();
+        // Refused check-ins are members who are not on the roster or already
+        // checked in.
+        if !present
+            .iter()
+            .any(|user_id| event.clone().check_in(*user_id, now).is_ok())
+        {
+            continue;
+        }
+        let (event, checked_in) = store.update(event.id, |event| {
+            Ok(present
+                .iter()
+                .filter(|user_id| event.check_in(**user_id, now).is_ok())
+                .count())
+        })?;
+        if checked_in > 0 {
+            publish(ctx, &event).await?;
+        }
+    }
+    Ok(())
+}
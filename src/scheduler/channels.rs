@@ -0,0 +1,52 @@
+use chrono::Utc;
+use poise::serenity_prelude::Context;
+
+use crate::storage::event_store;
+use crate::structs::client_structs::Error;
+use crate::util::channels::{channel_due, clean_up_channel, series_channel_due};
+
+/// Archives or deletes the channels of events and series that ended a while
+/// ago.
+pub async fn clean_up_event_channels(ctx: &Context) -> Result<(), Error> {
+    let store = event_store(ctx).await;
+    let now = Utc::now();
+    for event in store.all_events()? {
+        let Some(channel_id) = event.own_channel else {
+            continue;
+        };
+        let config = store.guild_config(event.server_id())?;
+        if !channel_due(&event, &config, now) {
+            continue;
+        }
+        // One channel the bot may not touch should not hold up the others.
+        match clean_up_channel(ctx, &config, channel_id, &event.title).await {
+            Ok(()) => {
+                store.update(event.id, |event| {
+                    event.own_channel = None;
+                    Ok(())
+                })?;
+            }
+            Err(err) => eprintln!("Could not clean up the channel of {}: {err}", event.title),
+        }
+    }
+    for mut series in store.all_series()? {
+        let Some(channel_id) = series.own_channel else {
+            continue;
+        };
+        let config = store.guild_config(series.template.server_id())?;
+        if !series_channel_due(&series, &config, now) {
+            continue;
+        }
+        match clean_up_channel(ctx, &config, channel_id, &series.template.title).await {
+            Ok(()) => {
+                series.own_channel = None;
+                store.save_series(&series)?;
+            }
+            Err(err) => eprintln!(
+                "Could not clean up the channel of the series {}: {err}",
+                series.template.title
+            ),
+        }
+    }
+    Ok(())
+}
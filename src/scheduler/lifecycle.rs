@@ -0,0 +1,32 @@
+use chrono::Utc;
+use poise::serenity_prelude::Context;
+
+use crate::storage::event_store;
+use crate::structs::client_structs::Error;
+use crate::structs::event::EventState;
+use crate::util::event::publish;
+use crate::util::scheduled_event::sync_scheduled_event;
+use crate::util::thread::archive_thread;
+
+/// Marks events as started and completed once their time has come, and
+/// records who attended completed ones.
+pub async fn advance_events(ctx: &Context) -> Result<(), Error> {
+    let store = event_store(ctx).await;
+    let now = Utc::now();
+    for event in store.all_events()? {
+        if !event.clone().advance(now) {
+            continue;
+        }
+        // Advance the stored event, so check-ins since the snapshot count.
+        let (mut event, advanced) = store.update(event.id, |event| Ok(event.advance(now)))?;
+        if advanced {
+            if event.state() == EventState::Completed {
+                store.save_attendance(&event.attendance())?;
+            }
+            publish(ctx, &event).await?;
+            sync_scheduled_event(ctx, &mut event).await;
+            archive_thread(ctx, &event).await;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,39 @@
+pub mod attendance;
+pub mod channels;
+pub mod lifecycle;
+pub mod reminders;
+pub mod series;
+pub mod voice;
+
+use std::time::Duration;
+
+use poise::serenity_prelude::Context;
+
+const TICK: Duration = Duration::from_secs(60);
+
+/// Background loop for everything that happens on a schedule rather than in
+/// response to a command. Started once the bot is ready.
+pub async fn run(ctx: Context) {
+    let mut interval = tokio::time::interval(TICK);
+    loop {
+        interval.tick().await;
+        if let Err(err) = series::create_due_occurrences(&ctx).await {
+            eprintln!("Could not create series occurrences: {err}");
+        }
+        if let Err(err) = reminders::send_due_reminders(&ctx).await {
+            eprintln!("Could not send reminders: {err}");
+        }
+        if let Err(err) = lifecycle::advance_events(&ctx).await {
+            eprintln!("Could not advance events: {err}");
+        }
+        if let Err(err) = channels::clean_up_event_channels(&ctx).await {
+            eprintln!("Could not clean up event channels: {err}");
+        }
+        if let Err(err) = voice::manage_voice_channels(&ctx).await {
+            eprintln!("Could not manage voice channels: {err}");
+        }
+        if let Err(err) = attendance::check_in_from_voice(&ctx).await {
+            eprintln!("Could not check in members from voice: {err}");
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use chrono::{Duration, Utc};
+use poise::serenity_prelude::{Context, Mentionable};
+
+use crate::storage::event_store;
+use crate::structs::client_structs::Error;
+use crate::structs::event::{Event, EventState, Participant, RsvpStatus};
+use crate::util::event::notify;
+use crate::util::timezone::Clock;
+
+/// How long after its start an event is still announced.
+const START_GRACE: Duration = Duration::minutes(30);
+
+/// Sends every reminder and start announcement that is due and has not been
+/// sent yet.
+pub async fn send_due_reminders(ctx: &Context) -> Result<(), Error> {
+    let store = event_store(ctx).await;
+    let now = Utc::now();
+    for event in store.all_events()? {
+        let scheduled = matches!(
+            event.state(),
+            EventState::Open | EventState::Locked | EventState::Started
+        );
+        if !scheduled || event.date() + START_GRACE < now {
+            continue;
+        }
+        // The start announcement is handled as a reminder without offset.
+        let due = event
+            .reminders
+            .offsets
+            .iter()
+            .copied()
+            .chain([Duration::zero()])
+            .filter(|offset| event.date() - *offset <= now);
+        // When several reminders are due at once, e.g. after a restart, only
+        // the most recent one is sent and the others are dropped.
+        let mut latest = None;
+        for offset in due {
+            if store.claim_reminder(event.id, offset)? {
+                latest = Some(latest.map_or(offset, |latest: Duration| latest.min(offset)));
+            }
+        }
+        let sent = match latest {
+            Some(offset) if offset.is_zero() => announce_start(ctx, &event).await,
+            Some(_) => remind(ctx, &event).await,
+            None => Ok(()),
+        };
+        // One channel the bot may not post to should not hold up the others.
+        if let Err(err) = sent {
+            eprintln!("Could not send the reminder of {}: {err}", event.title);
+        }
+    }
+    Ok(())
+}
+
+async fn dm_participants(ctx: &Context, event: &Event, content: impl Fn(&Clock) -> String) {
+    if !event.reminders.dm {
+        return;
+    }
+    let attending = event
+        .participants()
+        .iter()
+        .filter(|prt| prt.status != RsvpStatus::Declined)
+        .cloned()
+        .collect::<Vec<Participant>>();
+    notify(ctx, event, &attending, |_, clock| content(clock)).await;
+}
+
+async fn remind(ctx: &Context, event: &Event) -> Result<(), Error> {
+    let content = format!(
+        "Reminder: **{}** starts <t:{}:R> (<t:{}:F>).",
+        event.title,
+        event.date().timestamp(),
+        event.date().timestamp()
+    );
+    dm_participants(ctx, event, |clock| {
+        format!(
+            "Reminder: **{}** starts <t:{}:R> (<t:{}:F>, {}).",
+            event.title,
+            event.date().timestamp(),
+            event.date().timestamp(),
+            clock.format(event.date())
+        )
+    })
+    .await;
+    if let (Some(channel_id), Some(role)) = (event.channel_id(), event.reminders.ping_role) {
+        channel_id
+            .say(ctx, format!("{} {content}", role.mention()))
+            .await?;
+    }
+    Ok(())
+}
+
+async fn announce_start(ctx: &Context, event: &Event) -> Result<(), Error> {
+    let content = format!("**{}** is starting now!", event.title);
+    dm_participants(ctx, event, |_| content.clone()).await;
+    if let Some(channel_id) = event.channel_id() {
+        let content = match event.reminders.ping_role {
+            Some(role) => format!("{} {content}", role.mention()),
+            None => content,
+        };
+        channel_id.say(ctx, content).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use chrono::{Duration, Utc};
+use poise::serenity_prelude::Context;
+
+use crate::storage::event_store;
+use crate::structs::client_structs::Error;
+use crate::util::scheduled_event::sync_scheduled_event;
+use crate::util::thread::open_thread;
+
+/// How far ahead occurrences of a series are created.
+const HORIZON: Duration = Duration::days(7);
+
+/// Creates and posts every occurrence that starts within the horizon and does
+/// not exist yet.
+pub async fn create_due_occurrences(ctx: &Context) -> Result<(), Error> {
+    let store = event_store(ctx).await;
+    let now = Utc::now();
+    'series: for mut series in store.all_series()? {
+        for scheduled in series.due_occurrences(now + HORIZON) {
+            // Occurrences missed while the bot was offline are skipped.
+            if scheduled > now {
+                let mut event = series.occurrence(scheduled);
+                // One channel the bot may not post to should not hold up the
+                // other series. The occurrence is tried again on the next tick.
+                if let Err(err) = event.post_message(ctx, series.channel_id).await {
+                    eprintln!(
+                        "Could not post an occurrence of {}: {err}",
+                        series.template.title
+                    );
+                    continue 'series;
+                }
+                store.save_event(&event)?;
+                sync_scheduled_event(ctx, &mut event).await;
+                open_thread(ctx, &mut event).await;
+            }
+            series.generated_until = scheduled
+                .with_timezone(&series.recurrence.zone)
+                .date_naive();
+            store.save_series(&series)?;
+        }
+    }
+    Ok(())
+}
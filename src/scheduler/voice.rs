@@ -0,0 +1,90 @@
+use chrono::{Duration, Utc};
+use poise::serenity_prelude::Context;
+
+use crate::storage::event_store;
+use crate::structs::client_structs::Error;
+use crate::structs::event::{Event, EventState};
+use crate::util::channels::is_not_found;
+use crate::util::voice::{
+    close_voice_channel, open_voice_channel, sync_voice_access, voice_occupied,
+};
+
+/// How long before the start the voice channel is opened.
+const VOICE_LEAD: Duration = Duration::minutes(15);
+/// How long the voice channel has to stay empty after the event before it is
+/// deleted.
+const EMPTY_GRACE: Duration = Duration::minutes(10);
+
+/// Opens voice channels shortly before their events start, keeps their access
+/// in line with the roster and deletes them once they sat empty after the end.
+pub async fn manage_voice_channels(ctx: &Context) -> Result<(), Error> {
+    let store = event_store(ctx).await;
+    let now = Utc::now();
+    for mut event in store.all_events()? {
+        if !event.own_voice && event.voice.is_none() {
+            continue;
+        }
+        match update_voice_channel(ctx, &mut event, now).await {
+            Ok(true) => {
+                store.update(event.id, |stored| {
+                    stored.voice = event.voice;
+                    stored.voice_empty_since = event.voice_empty_since;
+                    Ok(())
+                })?;
+            }
+            Ok(false) => {}
+            // Deleted by hand, so the organizers do not want it back.
+            Err(err) if is_not_found(&err) => {
+                store.update(event.id, |stored| {
+                    stored.own_voice = false;
+                    stored.voice = None;
+                    stored.voice_empty_since = None;
+                    Ok(())
+                })?;
+            }
+            Err(err) => eprintln!(
+                "Could not update the voice channel of {}: {err}",
+                event.title
+            ),
+        }
+    }
+    Ok(())
+}
+
+/// Takes the next step for the voice channel of the event and tells whether
+/// the event changed.
+async fn update_voice_channel(
+    ctx: &Context,
+    event: &mut Event,
+    now: chrono::DateTime<Utc>,
+) -> poise::serenity_prelude::Result<bool> {
+    let over = matches!(event.state(), EventState::Completed | EventState::Cancelled)
+        || event.end() <= now;
+    let scheduled = matches!(
+        event.state(),
+        EventState::Open | EventState::Locked | EventState::Started
+    );
+    match event.voice {
+        None if !over && scheduled && event.date() - VOICE_LEAD <= now => {
+            open_voice_channel(ctx, event).await?;
+            Ok(true)
+        }
+        None => Ok(false),
+        Some(_) if !over => {
+            sync_voice_access(ctx, event).await?;
+            Ok(false)
+        }
+        Some(_) if voice_occupied(ctx, event) => Ok(event.voice_empty_since.take().is_some()),
+        Some(_) => match event.voice_empty_since {
+            None => {
+                event.voice_empty_since = Some(now);
+                Ok(true)
+            }
+            Some(since) if since + EMPTY_GRACE <= now => {
+                close_voice_channel(ctx, event).await?;
+                Ok(true)
+            }
+            Some(_) => Ok(false),
+        },
+    }
+}
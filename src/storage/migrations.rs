@@ -0,0 +1,199 @@
+use rusqlite::Connection;
+
+/// Schema migrations, applied in order. The index of the last applied
+/// migration is tracked in SQLite's `user_version` pragma, so new entries
+/// must only ever be appended.
+const MIGRATIONS: &[&str] = &[
+    "
+    CREATE TABLE events (
+        id TEXT PRIMARY KEY NOT NULL,
+        server_id INTEGER NOT NULL,
+        title TEXT NOT NULL,
+        creator TEXT NOT NULL,
+        date INTEGER NOT NULL
+    );
+    CREATE INDEX events_server_id ON events (server_id);
+
+    CREATE TABLE roles (
+        event_id TEXT NOT NULL REFERENCES events (id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        name TEXT NOT NULL,
+        amount INTEGER NOT NULL,
+        PRIMARY KEY (event_id, position)
+    );
+
+    CREATE TABLE role_flavors (
+        event_id TEXT NOT NULL REFERENCES events (id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        flavor TEXT NOT NULL,
+        amount INTEGER NOT NULL,
+        PRIMARY KEY (event_id, position)
+    );
+
+    CREATE TABLE participants (
+        event_id TEXT NOT NULL REFERENCES events (id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        user TEXT NOT NULL,
+        role TEXT NOT NULL,
+        flavor TEXT,
+        PRIMARY KEY (event_id, position)
+    );
+",
+    "
+    CREATE TABLE event_messages (
+        event_id TEXT NOT NULL REFERENCES events (id) ON DELETE CASCADE,
+        channel_id INTEGER NOT NULL,
+        message_id INTEGER NOT NULL,
+        PRIMARY KEY (channel_id, message_id)
+    );
+    CREATE INDEX event_messages_event_id ON event_messages (event_id);
+",
+    "
+    ALTER TABLE participants ADD COLUMN status TEXT NOT NULL DEFAULT 'accepted';
+",
+    "
+    ALTER TABLE events ADD COLUMN description TEXT NOT NULL DEFAULT '';
+",
+    "
+    ALTER TABLE events ADD COLUMN template INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE events ADD COLUMN series_id TEXT;
+    ALTER TABLE events ADD COLUMN occurrence INTEGER;
+
+    CREATE TABLE series (
+        id TEXT PRIMARY KEY NOT NULL REFERENCES events (id) ON DELETE CASCADE,
+        channel_id INTEGER NOT NULL,
+        frequency TEXT NOT NULL,
+        interval INTEGER NOT NULL,
+        weekdays TEXT NOT NULL,
+        until INTEGER,
+        count INTEGER,
+        generated_until TEXT NOT NULL
+    );
+",
+    "
+    ALTER TABLE events ADD COLUMN reminders TEXT NOT NULL DEFAULT '1440,15';
+    ALTER TABLE events ADD COLUMN dm_reminders INTEGER NOT NULL DEFAULT 1;
+    ALTER TABLE events ADD COLUMN ping_role INTEGER;
+
+    CREATE TABLE sent_reminders (
+        event_id TEXT NOT NULL REFERENCES events (id) ON DELETE CASCADE,
+        offset_minutes INTEGER NOT NULL,
+        PRIMARY KEY (event_id, offset_minutes)
+    );
+",
+    "
+    ALTER TABLE participants ADD COLUMN waitlisted INTEGER NOT NULL DEFAULT 0;
+",
+    "
+    ALTER TABLE events ADD COLUMN cancelled INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE events ADD COLUMN cancel_reason TEXT;
+",
+    "
+    ALTER TABLE events ADD COLUMN state TEXT NOT NULL DEFAULT 'open';
+    UPDATE events SET state = 'cancelled' WHERE cancelled = 1;
+    ALTER TABLE events DROP COLUMN cancelled;
+    ALTER TABLE events ADD COLUMN duration INTEGER NOT NULL DEFAULT 120;
+",
+    "
+    CREATE TABLE user_settings (
+        user_id INTEGER PRIMARY KEY NOT NULL,
+        timezone TEXT
+    );
+    ALTER TABLE series ADD COLUMN timezone TEXT NOT NULL DEFAULT '';
+",
+    "
+    CREATE TABLE guild_config (
+        guild_id INTEGER PRIMARY KEY NOT NULL,
+        event_channel INTEGER,
+        timezone TEXT,
+        locale TEXT NOT NULL,
+        reminders TEXT NOT NULL,
+        organizer_role INTEGER,
+        embed_color INTEGER NOT NULL,
+        own_channel INTEGER NOT NULL
+    );
+    ALTER TABLE events ADD COLUMN color INTEGER NOT NULL DEFAULT 10181046;
+",
+    "
+    CREATE TABLE event_organizers (
+        event_id TEXT NOT NULL REFERENCES events (id) ON DELETE CASCADE,
+        user_id INTEGER NOT NULL,
+        PRIMARY KEY (event_id, user_id)
+    );
+",
+    "
+    CREATE TABLE event_templates (
+        guild_id INTEGER NOT NULL,
+        name TEXT NOT NULL,
+        description TEXT NOT NULL,
+        roles TEXT NOT NULL,
+        flavors TEXT NOT NULL,
+        duration INTEGER NOT NULL,
+        PRIMARY KEY (guild_id, name)
+    );
+",
+    "
+    ALTER TABLE role_flavors ADD COLUMN role TEXT;
+    ALTER TABLE role_flavors ADD COLUMN min_count INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE role_flavors ADD COLUMN max_count INTEGER;
+    UPDATE role_flavors SET max_count = NULLIF(amount, 0);
+    ALTER TABLE role_flavors DROP COLUMN amount;
+",
+    "
+    CREATE TABLE feed_tokens (
+        token TEXT PRIMARY KEY NOT NULL,
+        kind TEXT NOT NULL,
+        owner_id INTEGER NOT NULL,
+        UNIQUE (kind, owner_id)
+    );
+",
+    "
+    ALTER TABLE events ADD COLUMN native_event INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE events ADD COLUMN scheduled_event INTEGER;
+",
+    "
+    ALTER TABLE events ADD COLUMN own_thread INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE events ADD COLUMN thread INTEGER;
+",
+    "
+    ALTER TABLE events ADD COLUMN own_channel INTEGER;
+    ALTER TABLE guild_config ADD COLUMN channel_category INTEGER;
+    ALTER TABLE guild_config ADD COLUMN archive_category INTEGER;
+    ALTER TABLE guild_config ADD COLUMN channel_cleanup INTEGER NOT NULL DEFAULT 1440;
+    ALTER TABLE guild_config ADD COLUMN log_channel INTEGER;
+",
+    "
+    ALTER TABLE events ADD COLUMN own_voice INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE events ADD COLUMN voice_waitlist INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE events ADD COLUMN voice INTEGER;
+    ALTER TABLE events ADD COLUMN voice_empty_since INTEGER;
+",
+    "
+    ALTER TABLE participants ADD COLUMN checked_in INTEGER;
+    ALTER TABLE events ADD COLUMN auto_check_in INTEGER NOT NULL DEFAULT 0;
+    CREATE TABLE attendance (
+        event_id TEXT NOT NULL,
+        guild_id INTEGER NOT NULL,
+        user_id INTEGER NOT NULL,
+        title TEXT NOT NULL,
+        date INTEGER NOT NULL,
+        status TEXT NOT NULL,
+        PRIMARY KEY (event_id, user_id)
+    );
+    CREATE INDEX attendance_by_member ON attendance (guild_id, user_id);
+",
+    "
+    ALTER TABLE series ADD COLUMN own_channel INTEGER;
+",
+];
+
+pub fn run(conn: &mut Connection) -> rusqlite::Result<()> {
+    let applied: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    for (version, migration) in MIGRATIONS.iter().enumerate().skip(applied as usize) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", version as i64 + 1)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
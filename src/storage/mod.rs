@@ -0,0 +1,127 @@
+pub mod migrations;
+pub mod sqlite;
+
+use std::sync::Arc;
+
+use crate::structs::attendance::AttendanceRecord;
+use crate::structs::client_structs::{Error, EventData};
+use crate::structs::event::Event;
+use crate::structs::event_error::EventError;
+use crate::structs::feed::FeedOwner;
+use crate::structs::guild_config::GuildConfig;
+use crate::structs::series::Series;
+use crate::structs::template::EventTemplate;
+use chrono::Duration;
+use chrono_tz::Tz;
+use poise::serenity_prelude::{Context, GuildId, UserId};
+use uuid::Uuid;
+
+/// Persistence backend for events, their rosters and user and server settings.
+pub trait EventStore: Send + Sync {
+    /// Inserts the event, or replaces the stored copy with the same id.
+    fn save_event(&self, event: &Event) -> Result<(), Error>;
+
+    fn event(&self, id: Uuid) -> Result<Option<Event>, Error>;
+
+    /// Loads the event, lets `change` edit it and stores the result in one
+    /// transaction, so concurrent changes can't overwrite each other. Nothing
+    /// is stored if `change` fails. Returns `None` if there is no such event.
+    fn update_event(
+        &self,
+        id: Uuid,
+        change: &mut dyn FnMut(&mut Event) -> Result<(), Error>,
+    ) -> Result<Option<Event>, Error>;
+
+    /// All events belonging to the given server, ordered by date.
+    fn events(&self, server_id: GuildId) -> Result<Vec<Event>, Error>;
+
+    fn all_events(&self) -> Result<Vec<Event>, Error>;
+
+    fn delete_event(&self, id: Uuid) -> Result<(), Error>;
+
+    /// Inserts the series together with its template, or replaces the stored copy.
+    fn save_series(&self, series: &Series) -> Result<(), Error>;
+
+    fn series(&self, id: Uuid) -> Result<Option<Series>, Error>;
+
+    fn all_series(&self) -> Result<Vec<Series>, Error>;
+
+    /// Removes the series. Occurrences that were already created are kept.
+    fn delete_series(&self, id: Uuid) -> Result<(), Error>;
+
+    /// Records that the reminder `offset` before the event is being sent.
+    /// Returns `false` if it was claimed before, so it is never sent twice.
+    fn claim_reminder(&self, event_id: Uuid, offset: Duration) -> Result<bool, Error>;
+
+    /// Forgets which reminders of the event were sent, e.g. after it was moved.
+    fn reset_reminders(&self, event_id: Uuid) -> Result<(), Error>;
+
+    /// Zone the user picked for reading and showing dates, if any.
+    fn user_timezone(&self, user_id: UserId) -> Result<Option<Tz>, Error>;
+
+    /// Stores the zone of the user. `None` goes back to the default.
+    fn set_user_timezone(&self, user_id: UserId, zone: Option<Tz>) -> Result<(), Error>;
+
+    /// Settings of the server, or the defaults if it never changed any.
+    fn guild_config(&self, guild_id: GuildId) -> Result<GuildConfig, Error>;
+
+    fn save_guild_config(&self, config: &GuildConfig) -> Result<(), Error>;
+
+    /// Inserts the template, or replaces the one of the server with the same name.
+    fn save_template(&self, template: &EventTemplate) -> Result<(), Error>;
+
+    fn template(&self, guild_id: GuildId, name: &str) -> Result<Option<EventTemplate>, Error>;
+
+    /// All templates of the server, ordered by name.
+    fn templates(&self, guild_id: GuildId) -> Result<Vec<EventTemplate>, Error>;
+
+    /// Removes the template. Returns `false` if the server had none by that name.
+    fn delete_template(&self, guild_id: GuildId, name: &str) -> Result<bool, Error>;
+
+    /// Secret token of the calendar feed, if one was handed out.
+    fn feed_token(&self, owner: FeedOwner) -> Result<Option<String>, Error>;
+
+    /// Hands out a new token for the feed. The previous one stops working.
+    fn rotate_feed_token(&self, owner: FeedOwner) -> Result<String, Error>;
+
+    /// Whose feed the token opens.
+    fn feed_owner(&self, token: &str) -> Result<Option<FeedOwner>, Error>;
+
+    /// Stores how the participants attended, replacing earlier records of the
+    /// same event.
+    fn save_attendance(&self, records: &[AttendanceRecord]) -> Result<(), Error>;
+
+    /// Attendance history of the member on the server, newest first.
+    fn attendance(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Vec<AttendanceRecord>, Error>;
+}
+
+impl dyn EventStore {
+    /// Typed front of [`EventStore::update_event`]: applies `change` to the
+    /// stored event and returns the updated event together with what `change`
+    /// returned. Fails with [`EventError::NotFound`] if the event is gone.
+    pub fn update<T>(
+        &self,
+        id: Uuid,
+        change: impl FnOnce(&mut Event) -> Result<T, Error>,
+    ) -> Result<(Event, T), Error> {
+        let mut change = Some(change);
+        let mut output = None;
+        let event = self
+            .update_event(id, &mut |event| {
+                let change = change.take().ok_or("The change was already applied.")?;
+                output = Some(change(event)?);
+                Ok(())
+            })?
+            .ok_or(EventError::NotFound)?;
+        let output = output.ok_or("The change was not applied.")?;
+        Ok((event, output))
+    }
+}
+
+pub async fn event_store(ctx: &Context) -> Arc<dyn EventStore> {
+    ctx.data.read().await.get::<EventData>().unwrap().clone()
+}
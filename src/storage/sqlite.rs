@@ -0,0 +1,802 @@
+use std::path::Path;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Duration, Locale, NaiveDate, Utc, Weekday};
+use chrono_tz::Tz;
+use poise::serenity_prelude::{
+    ChannelId, Color, GuildId, MessageId, RoleId, ScheduledEventId, User, UserId,
+};
+use rusqlite::OptionalExtension;
+use rusqlite::{params, Connection, Transaction};
+use uuid::Uuid;
+
+use super::{migrations, EventStore};
+use crate::structs::attendance::{AttendanceRecord, AttendanceStatus};
+use crate::structs::client_structs::Error;
+use crate::structs::event::{
+    Event, EventMessage, EventState, Occurrence, Participant, Role, RoleFlavor, RsvpStatus,
+};
+use crate::structs::feed::FeedOwner;
+use crate::structs::guild_config::GuildConfig;
+use crate::structs::recurrence::{Frequency, Recurrence, RecurrenceEnd};
+use crate::structs::series::Series;
+use crate::structs::template::EventTemplate;
+use crate::util::event_parser::{format_flavors, format_roles, parse_flavors, parse_roles};
+use crate::util::timezone::default_timezone;
+
+const DAY_FORMAT: &str = "%Y-%m-%d";
+
+fn from_timestamp(timestamp: i64) -> Result<DateTime<Utc>, Error> {
+    Ok(DateTime::from_timestamp(timestamp, 0).ok_or("Stored timestamp out of range.")?)
+}
+
+/// Reminder offsets are stored as comma-separated minutes.
+fn offsets_to_sql(offsets: &[Duration]) -> String {
+    offsets
+        .iter()
+        .map(|offset| offset.num_minutes().to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+fn offsets_from_sql(minutes: &str) -> Result<Vec<Duration>, Error> {
+    Ok(minutes
+        .split(',')
+        .filter(|minutes| !minutes.is_empty())
+        .map(|minutes| minutes.parse::<i64>().map(Duration::minutes))
+        .collect::<Result<_, _>>()?)
+}
+
+pub struct SqliteEventStore {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteEventStore {
+    /// Opens (or creates) the database at `path` and brings its schema up to date.
+    pub fn open(path: impl AsRef<Path>) -> Result<SqliteEventStore, Error> {
+        let mut conn = Connection::open(path)?;
+        conn.pragma_update(None, "foreign_keys", "ON")?;
+        migrations::run(&mut conn)?;
+        Ok(SqliteEventStore {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn load_events(
+        conn: &Connection,
+        filter: &str,
+        params: impl rusqlite::Params,
+    ) -> Result<Vec<Event>, Error> {
+        let mut statement = conn.prepare(&format!(
+            "SELECT id, server_id, title, creator, date, description, series_id, occurrence,
+                reminders, dm_reminders, ping_role, state, cancel_reason, duration, color,
+                native_event, scheduled_event, own_thread, thread, own_channel, own_voice,
+                voice_waitlist, voice, voice_empty_since, auto_check_in
+            FROM events {filter}"
+        ))?;
+        let mut rows = statement.query(params)?;
+        let mut events = Vec::new();
+        while let Some(row) = rows.next()? {
+            let mut event = Event::new(
+                serde_json::from_str::<User>(&row.get::<_, String>("creator")?)?,
+                row.get("title")?,
+                from_timestamp(row.get("date")?)?,
+                GuildId::new(row.get::<_, i64>("server_id")? as u64),
+            );
+            event.id = Uuid::parse_str(&row.get::<_, String>("id")?)?;
+            event.description = row.get("description")?;
+            if let Some(series_id) = row.get::<_, Option<String>>("series_id")? {
+                event.series = Some(Occurrence {
+                    series_id: Uuid::parse_str(&series_id)?,
+                    scheduled: from_timestamp(row.get("occurrence")?)?,
+                });
+            }
+            event.reminders.offsets = offsets_from_sql(&row.get::<_, String>("reminders")?)?;
+            event.reminders.dm = row.get("dm_reminders")?;
+            event.reminders.ping_role = row
+                .get::<_, Option<i64>>("ping_role")?
+                .map(|role| RoleId::new(role as u64));
+            event.state = row.get::<_, String>("state")?.parse::<EventState>()?;
+            event.cancel_reason = row.get("cancel_reason")?;
+            event.duration = Duration::minutes(row.get("duration")?);
+            event.color = Color::new(row.get("color")?);
+            event.native_event = row.get("native_event")?;
+            event.scheduled_event = row
+                .get::<_, Option<i64>>("scheduled_event")?
+                .map(|id| ScheduledEventId::new(id as u64));
+            event.own_thread = row.get("own_thread")?;
+            event.thread = row
+                .get::<_, Option<i64>>("thread")?
+                .map(|id| ChannelId::new(id as u64));
+            event.own_channel = row
+                .get::<_, Option<i64>>("own_channel")?
+                .map(|id| ChannelId::new(id as u64));
+            event.own_voice = row.get("own_voice")?;
+            event.voice_waitlist = row.get("voice_waitlist")?;
+            event.voice = row
+                .get::<_, Option<i64>>("voice")?
+                .map(|id| ChannelId::new(id as u64));
+            event.voice_empty_since = row
+                .get::<_, Option<i64>>("voice_empty_since")?
+                .map(from_timestamp)
+                .transpose()?;
+            event.auto_check_in = row.get("auto_check_in")?;
+            Self::load_roster(conn, &mut event)?;
+            events.push(event);
+        }
+        Ok(events)
+    }
+
+    fn load_series(
+        conn: &Connection,
+        filter: &str,
+        params: impl rusqlite::Params,
+    ) -> Result<Vec<Series>, Error> {
+        let mut statement = conn.prepare(&format!(
+            "SELECT id, channel_id, frequency, interval, weekdays, until, count, generated_until,
+                timezone, own_channel
+            FROM series {filter}"
+        ))?;
+        let mut rows = statement.query(params)?;
+        let mut series = Vec::new();
+        while let Some(row) = rows.next()? {
+            let id = row.get::<_, String>("id")?;
+            let template = Self::load_events(conn, "WHERE id = ?1", [&id])?
+                .pop()
+                .ok_or("Stored series has no template.")?;
+            let frequency = match row.get::<_, String>("frequency")?.as_str() {
+                "daily" => Frequency::Daily,
+                "weekly" => Frequency::Weekly,
+                other => return Err(format!("Unknown series frequency {other}.").into()),
+            };
+            let end = match (
+                row.get::<_, Option<i64>>("until")?,
+                row.get::<_, Option<u32>>("count")?,
+            ) {
+                (Some(until), _) => RecurrenceEnd::Until(from_timestamp(until)?),
+                (None, Some(count)) => RecurrenceEnd::Count(count),
+                (None, None) => RecurrenceEnd::Never,
+            };
+            series.push(Series {
+                id: Uuid::parse_str(&id)?,
+                template,
+                recurrence: Recurrence {
+                    frequency,
+                    interval: row.get("interval")?,
+                    weekdays: row
+                        .get::<_, String>("weekdays")?
+                        .split(',')
+                        .filter(|day| !day.is_empty())
+                        .map(|day| day.parse::<Weekday>())
+                        .collect::<Result<_, _>>()?,
+                    end,
+                    // Series from before zones were stored used the host's zone.
+                    zone: match row.get::<_, String>("timezone")?.as_str() {
+                        "" => default_timezone(),
+                        zone => zone.parse::<Tz>()?,
+                    },
+                },
+                channel_id: ChannelId::new(row.get::<_, i64>("channel_id")? as u64),
+                own_channel: row
+                    .get::<_, Option<i64>>("own_channel")?
+                    .map(|id| ChannelId::new(id as u64)),
+                generated_until: NaiveDate::parse_from_str(
+                    &row.get::<_, String>("generated_until")?,
+                    DAY_FORMAT,
+                )?,
+            });
+        }
+        Ok(series)
+    }
+
+    fn load_roster(conn: &Connection, event: &mut Event) -> Result<(), Error> {
+        let id = event.id.to_string();
+        event.needed_roles = conn
+            .prepare("SELECT name, amount FROM roles WHERE event_id = ?1 ORDER BY position")?
+            .query_map([&id], |row| {
+                Ok(Role {
+                    name: row.get(0)?,
+                    amount: row.get(1)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+        event.needed_flavors = conn
+            .prepare(
+                "SELECT flavor, role, min_count, max_count FROM role_flavors
+                WHERE event_id = ?1 ORDER BY position",
+            )?
+            .query_map([&id], |row| {
+                Ok(RoleFlavor {
+                    flavor: row.get(0)?,
+                    role: row.get(1)?,
+                    min: row.get(2)?,
+                    max: row.get(3)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+        event.event_messages = conn
+            .prepare("SELECT channel_id, message_id FROM event_messages WHERE event_id = ?1")?
+            .query_map([&id], |row| {
+                Ok(EventMessage {
+                    channel_id: ChannelId::new(row.get::<_, i64>(0)? as u64),
+                    message_id: MessageId::new(row.get::<_, i64>(1)? as u64),
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+        event.co_organizers = conn
+            .prepare("SELECT user_id FROM event_organizers WHERE event_id = ?1 ORDER BY rowid")?
+            .query_map([&id], |row| Ok(UserId::new(row.get::<_, i64>(0)? as u64)))?
+            .collect::<Result<_, _>>()?;
+        let mut statement = conn.prepare(
+            "SELECT user, role, flavor, status, waitlisted, checked_in FROM participants
+            WHERE event_id = ?1 ORDER BY position",
+        )?;
+        let mut rows = statement.query([&id])?;
+        while let Some(row) = rows.next()? {
+            let role = row.get::<_, String>("role")?;
+            let role = match role.is_empty() {
+                true => Role::none(),
+                false => event
+                    .needed_roles
+                    .iter()
+                    .find(|rl| rl.name == role)
+                    .cloned()
+                    .ok_or("Stored participant has an unknown role.")?,
+            };
+            let flavor = row
+                .get::<_, Option<String>>("flavor")?
+                .and_then(|flavor| event.find_flavor(&role.name, &flavor).cloned());
+            let participant = Participant {
+                id: serde_json::from_str(&row.get::<_, String>("user")?)?,
+                role,
+                flavor,
+                status: row.get::<_, String>("status")?.parse::<RsvpStatus>()?,
+                checked_in: row
+                    .get::<_, Option<i64>>("checked_in")?
+                    .map(from_timestamp)
+                    .transpose()?,
+            };
+            if row.get("waitlisted")? {
+                event.waitlist.push(participant);
+            } else {
+                event.participants.push(participant);
+            }
+        }
+        Ok(())
+    }
+
+    fn write_event(tx: &Transaction, event: &Event, template: bool) -> Result<(), Error> {
+        tx.execute(
+            "INSERT INTO events (
+                id, server_id, title, creator, date, description, template, series_id, occurrence,
+                reminders, dm_reminders, ping_role, state, cancel_reason, duration, color,
+                native_event, scheduled_event, own_thread, thread, own_channel, own_voice,
+                voice_waitlist, voice, voice_empty_since, auto_check_in
+            )
+            VALUES (
+                ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18,
+                ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26
+            )
+            ON CONFLICT (id) DO UPDATE SET
+                server_id = excluded.server_id,
+                title = excluded.title,
+                creator = excluded.creator,
+                date = excluded.date,
+                description = excluded.description,
+                template = excluded.template,
+                series_id = excluded.series_id,
+                occurrence = excluded.occurrence,
+                reminders = excluded.reminders,
+                dm_reminders = excluded.dm_reminders,
+                ping_role = excluded.ping_role,
+                state = excluded.state,
+                cancel_reason = excluded.cancel_reason,
+                duration = excluded.duration,
+                color = excluded.color,
+                native_event = excluded.native_event,
+                scheduled_event = excluded.scheduled_event,
+                own_thread = excluded.own_thread,
+                thread = excluded.thread,
+                own_channel = excluded.own_channel,
+                own_voice = excluded.own_voice,
+                voice_waitlist = excluded.voice_waitlist,
+                voice = excluded.voice,
+                voice_empty_since = excluded.voice_empty_since,
+                auto_check_in = excluded.auto_check_in",
+            params![
+                event.id.to_string(),
+                event.server_id.get() as i64,
+                event.title,
+                serde_json::to_string(&event.creator)?,
+                event.date.timestamp(),
+                event.description,
+                template,
+                event.series.as_ref().map(|occ| occ.series_id.to_string()),
+                event.series.as_ref().map(|occ| occ.scheduled.timestamp()),
+                offsets_to_sql(&event.reminders.offsets),
+                event.reminders.dm,
+                event.reminders.ping_role.map(|role| role.get() as i64),
+                event.state.as_str(),
+                event.cancel_reason,
+                event.duration.num_minutes(),
+                event.color.0,
+                event.native_event,
+                event.scheduled_event.map(|id| id.get() as i64),
+                event.own_thread,
+                event.thread.map(|id| id.get() as i64),
+                event.own_channel.map(|id| id.get() as i64),
+                event.own_voice,
+                event.voice_waitlist,
+                event.voice.map(|id| id.get() as i64),
+                event.voice_empty_since.map(|since| since.timestamp()),
+                event.auto_check_in
+            ],
+        )?;
+        Self::write_roster(tx, event)
+    }
+
+    fn write_roster(tx: &Transaction, event: &Event) -> Result<(), Error> {
+        let id = event.id.to_string();
+        for table in [
+            "roles",
+            "role_flavors",
+            "participants",
+            "event_messages",
+            "event_organizers",
+        ] {
+            tx.execute(&format!("DELETE FROM {table} WHERE event_id = ?1"), [&id])?;
+        }
+        for (position, role) in event.needed_roles.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO roles (event_id, position, name, amount) VALUES (?1, ?2, ?3, ?4)",
+                params![id, position, role.name, role.amount],
+            )?;
+        }
+        for (position, flavor) in event.needed_flavors.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO role_flavors (event_id, position, flavor, role, min_count, max_count)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    id,
+                    position,
+                    flavor.flavor,
+                    flavor.role,
+                    flavor.min,
+                    flavor.max
+                ],
+            )?;
+        }
+        let roster = event.participants.iter().map(|prt| (prt, false));
+        let waitlist = event.waitlist.iter().map(|prt| (prt, true));
+        for (position, (participant, waitlisted)) in roster.chain(waitlist).enumerate() {
+            tx.execute(
+                "INSERT INTO participants (
+                    event_id, position, user, role, flavor, status, waitlisted, checked_in
+                )
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    id,
+                    position,
+                    serde_json::to_string(&participant.id)?,
+                    participant.role.name,
+                    participant.flavor.as_ref().map(|flv| &flv.flavor),
+                    participant.status.as_str(),
+                    waitlisted,
+                    participant.checked_in.map(|at| at.timestamp())
+                ],
+            )?;
+        }
+        for posted in event.event_messages.iter() {
+            tx.execute(
+                "INSERT INTO event_messages (event_id, channel_id, message_id) VALUES (?1, ?2, ?3)",
+                params![
+                    id,
+                    posted.channel_id.get() as i64,
+                    posted.message_id.get() as i64
+                ],
+            )?;
+        }
+        for organizer in event.co_organizers.iter() {
+            tx.execute(
+                "INSERT INTO event_organizers (event_id, user_id) VALUES (?1, ?2)",
+                params![id, organizer.get() as i64],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Templates of the server, only the one called `name` if given.
+    fn query_templates(
+        &self,
+        guild_id: GuildId,
+        name: Option<&str>,
+    ) -> Result<Vec<EventTemplate>, Error> {
+        let conn = self.conn.lock().unwrap();
+        let mut statement = conn.prepare(
+            "SELECT name, description, roles, flavors, duration FROM event_templates
+            WHERE guild_id = ?1 AND (?2 IS NULL OR name = ?2) ORDER BY name",
+        )?;
+        let mut rows = statement.query(params![guild_id.get() as i64, name])?;
+        let mut templates = Vec::new();
+        while let Some(row) = rows.next()? {
+            templates.push(EventTemplate {
+                guild_id,
+                name: row.get("name")?,
+                description: row.get("description")?,
+                roles: parse_roles(&row.get::<_, String>("roles")?)?,
+                flavors: parse_flavors(&row.get::<_, String>("flavors")?)?,
+                duration: Duration::minutes(row.get("duration")?),
+            });
+        }
+        Ok(templates)
+    }
+}
+
+impl EventStore for SqliteEventStore {
+    fn save_event(&self, event: &Event) -> Result<(), Error> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        Self::write_event(&tx, event, false)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn event(&self, id: Uuid) -> Result<Option<Event>, Error> {
+        let conn = self.conn.lock().unwrap();
+        Ok(Self::load_events(&conn, "WHERE template = 0 AND id = ?1", [id.to_string()])?.pop())
+    }
+
+    fn update_event(
+        &self,
+        id: Uuid,
+        change: &mut dyn FnMut(&mut Event) -> Result<(), Error>,
+    ) -> Result<Option<Event>, Error> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let Some(mut event) =
+            Self::load_events(&tx, "WHERE template = 0 AND id = ?1", [id.to_string()])?.pop()
+        else {
+            return Ok(None);
+        };
+        change(&mut event)?;
+        Self::write_event(&tx, &event, false)?;
+        tx.commit()?;
+        Ok(Some(event))
+    }
+
+    fn events(&self, server_id: GuildId) -> Result<Vec<Event>, Error> {
+        let conn = self.conn.lock().unwrap();
+        Self::load_events(
+            &conn,
+            "WHERE template = 0 AND server_id = ?1 ORDER BY date",
+            [server_id.get() as i64],
+        )
+    }
+
+    fn all_events(&self) -> Result<Vec<Event>, Error> {
+        let conn = self.conn.lock().unwrap();
+        Self::load_events(&conn, "WHERE template = 0 ORDER BY date", [])
+    }
+
+    fn delete_event(&self, id: Uuid) -> Result<(), Error> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM events WHERE id = ?1", [id.to_string()])?;
+        Ok(())
+    }
+
+    fn save_series(&self, series: &Series) -> Result<(), Error> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        Self::write_event(&tx, &series.template, true)?;
+        let recurrence = &series.recurrence;
+        tx.execute(
+            "INSERT INTO series (
+                id, channel_id, frequency, interval, weekdays, until, count, generated_until,
+                timezone, own_channel
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+            ON CONFLICT (id) DO UPDATE SET
+                channel_id = excluded.channel_id,
+                frequency = excluded.frequency,
+                interval = excluded.interval,
+                weekdays = excluded.weekdays,
+                until = excluded.until,
+                count = excluded.count,
+                generated_until = excluded.generated_until,
+                timezone = excluded.timezone,
+                own_channel = excluded.own_channel",
+            params![
+                series.id.to_string(),
+                series.channel_id.get() as i64,
+                recurrence.frequency.as_str(),
+                recurrence.interval,
+                recurrence
+                    .weekdays
+                    .iter()
+                    .map(|day| day.to_string())
+                    .collect::<Vec<String>>()
+                    .join(","),
+                match recurrence.end {
+                    RecurrenceEnd::Until(until) => Some(until.timestamp()),
+                    _ => None,
+                },
+                match recurrence.end {
+                    RecurrenceEnd::Count(count) => Some(count),
+                    _ => None,
+                },
+                series.generated_until.format(DAY_FORMAT).to_string(),
+                recurrence.zone.name(),
+                series.own_channel.map(|id| id.get() as i64)
+            ],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn series(&self, id: Uuid) -> Result<Option<Series>, Error> {
+        let conn = self.conn.lock().unwrap();
+        Ok(Self::load_series(&conn, "WHERE id = ?1", [id.to_string()])?.pop())
+    }
+
+    fn all_series(&self) -> Result<Vec<Series>, Error> {
+        let conn = self.conn.lock().unwrap();
+        Self::load_series(&conn, "", [])
+    }
+
+    fn claim_reminder(&self, event_id: Uuid, offset: Duration) -> Result<bool, Error> {
+        let conn = self.conn.lock().unwrap();
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO sent_reminders (event_id, offset_minutes) VALUES (?1, ?2)",
+            params![event_id.to_string(), offset.num_minutes()],
+        )?;
+        Ok(inserted == 1)
+    }
+
+    fn reset_reminders(&self, event_id: Uuid) -> Result<(), Error> {
+        self.conn.lock().unwrap().execute(
+            "DELETE FROM sent_reminders WHERE event_id = ?1",
+            params![event_id.to_string()],
+        )?;
+        Ok(())
+    }
+
+    fn user_timezone(&self, user_id: UserId) -> Result<Option<Tz>, Error> {
+        let zone = self
+            .conn
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT timezone FROM user_settings WHERE user_id = ?1",
+                [user_id.get() as i64],
+                |row| row.get::<_, Option<String>>(0),
+            )
+            .optional()?
+            .flatten();
+        Ok(zone.map(|zone| zone.parse::<Tz>()).transpose()?)
+    }
+
+    fn set_user_timezone(&self, user_id: UserId, zone: Option<Tz>) -> Result<(), Error> {
+        self.conn.lock().unwrap().execute(
+            "INSERT INTO user_settings (user_id, timezone) VALUES (?1, ?2)
+            ON CONFLICT (user_id) DO UPDATE SET timezone = excluded.timezone",
+            params![user_id.get() as i64, zone.map(|zone| zone.name())],
+        )?;
+        Ok(())
+    }
+
+    fn guild_config(&self, guild_id: GuildId) -> Result<GuildConfig, Error> {
+        let conn = self.conn.lock().unwrap();
+        let mut statement = conn.prepare(
+            "SELECT event_channel, timezone, locale, reminders, organizer_role, embed_color,
+                own_channel, channel_category, archive_category, channel_cleanup, log_channel
+            FROM guild_config WHERE guild_id = ?1",
+        )?;
+        let mut rows = statement.query([guild_id.get() as i64])?;
+        let mut config = GuildConfig::new(guild_id);
+        if let Some(row) = rows.next()? {
+            config.event_channel = row
+                .get::<_, Option<i64>>("event_channel")?
+                .map(|channel| ChannelId::new(channel as u64));
+            config.timezone = row
+                .get::<_, Option<String>>("timezone")?
+                .map(|zone| zone.parse::<Tz>())
+                .transpose()?;
+            config.locale = row
+                .get::<_, String>("locale")?
+                .parse::<Locale>()
+                .map_err(|_| "Stored locale is unknown.")?;
+            config.reminder_offsets = offsets_from_sql(&row.get::<_, String>("reminders")?)?;
+            config.organizer_role = row
+                .get::<_, Option<i64>>("organizer_role")?
+                .map(|role| RoleId::new(role as u64));
+            config.embed_color = Color::new(row.get("embed_color")?);
+            config.own_channel = row.get("own_channel")?;
+            config.channel_category = row
+                .get::<_, Option<i64>>("channel_category")?
+                .map(|channel| ChannelId::new(channel as u64));
+            config.archive_category = row
+                .get::<_, Option<i64>>("archive_category")?
+                .map(|channel| ChannelId::new(channel as u64));
+            config.channel_cleanup = Duration::minutes(row.get("channel_cleanup")?);
+            config.log_channel = row
+                .get::<_, Option<i64>>("log_channel")?
+                .map(|channel| ChannelId::new(channel as u64));
+        }
+        Ok(config)
+    }
+
+    fn save_guild_config(&self, config: &GuildConfig) -> Result<(), Error> {
+        self.conn.lock().unwrap().execute(
+            "INSERT INTO guild_config (
+                guild_id, event_channel, timezone, locale, reminders, organizer_role, embed_color,
+                own_channel, channel_category, archive_category, channel_cleanup, log_channel
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+            ON CONFLICT (guild_id) DO UPDATE SET
+                event_channel = excluded.event_channel,
+                timezone = excluded.timezone,
+                locale = excluded.locale,
+                reminders = excluded.reminders,
+                organizer_role = excluded.organizer_role,
+                embed_color = excluded.embed_color,
+                own_channel = excluded.own_channel,
+                channel_category = excluded.channel_category,
+                archive_category = excluded.archive_category,
+                channel_cleanup = excluded.channel_cleanup,
+                log_channel = excluded.log_channel",
+            params![
+                config.guild_id.get() as i64,
+                config.event_channel.map(|channel| channel.get() as i64),
+                config.timezone.map(|zone| zone.name()),
+                config.locale.to_string(),
+                offsets_to_sql(&config.reminder_offsets),
+                config.organizer_role.map(|role| role.get() as i64),
+                config.embed_color.0,
+                config.own_channel,
+                config.channel_category.map(|channel| channel.get() as i64),
+                config.archive_category.map(|channel| channel.get() as i64),
+                config.channel_cleanup.num_minutes(),
+                config.log_channel.map(|channel| channel.get() as i64)
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn save_template(&self, template: &EventTemplate) -> Result<(), Error> {
+        self.conn.lock().unwrap().execute(
+            "INSERT INTO event_templates (guild_id, name, description, roles, flavors, duration)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            ON CONFLICT (guild_id, name) DO UPDATE SET
+                description = excluded.description,
+                roles = excluded.roles,
+                flavors = excluded.flavors,
+                duration = excluded.duration",
+            params![
+                template.guild_id.get() as i64,
+                template.name,
+                template.description,
+                format_roles(&template.roles),
+                format_flavors(&template.flavors),
+                template.duration.num_minutes()
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn template(&self, guild_id: GuildId, name: &str) -> Result<Option<EventTemplate>, Error> {
+        Ok(self
+            .query_templates(guild_id, Some(name))?
+            .into_iter()
+            .next())
+    }
+
+    fn templates(&self, guild_id: GuildId) -> Result<Vec<EventTemplate>, Error> {
+        self.query_templates(guild_id, None)
+    }
+
+    fn delete_template(&self, guild_id: GuildId, name: &str) -> Result<bool, Error> {
+        let deleted = self.conn.lock().unwrap().execute(
+            "DELETE FROM event_templates WHERE guild_id = ?1 AND name = ?2",
+            params![guild_id.get() as i64, name],
+        )?;
+        Ok(deleted > 0)
+    }
+
+    fn feed_token(&self, owner: FeedOwner) -> Result<Option<String>, Error> {
+        Ok(self
+            .conn
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT token FROM feed_tokens WHERE kind = ?1 AND owner_id = ?2",
+                params![owner.kind(), owner.id() as i64],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    fn rotate_feed_token(&self, owner: FeedOwner) -> Result<String, Error> {
+        let token = Uuid::new_v4().simple().to_string();
+        self.conn.lock().unwrap().execute(
+            "INSERT INTO feed_tokens (token, kind, owner_id) VALUES (?1, ?2, ?3)
+            ON CONFLICT (kind, owner_id) DO UPDATE SET token = excluded.token",
+            params![token, owner.kind(), owner.id() as i64],
+        )?;
+        Ok(token)
+    }
+
+    fn feed_owner(&self, token: &str) -> Result<Option<FeedOwner>, Error> {
+        let owner = self
+            .conn
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT kind, owner_id FROM feed_tokens WHERE token = ?1",
+                [token],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as u64)),
+            )
+            .optional()?;
+        Ok(match owner {
+            Some((kind, id)) if kind == "user" => Some(FeedOwner::User(UserId::new(id))),
+            Some((kind, id)) if kind == "guild" => Some(FeedOwner::Guild(GuildId::new(id))),
+            _ => None,
+        })
+    }
+
+    fn save_attendance(&self, records: &[AttendanceRecord]) -> Result<(), Error> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for record in records {
+            tx.execute(
+                "INSERT INTO attendance (event_id, guild_id, user_id, title, date, status)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                ON CONFLICT (event_id, user_id) DO UPDATE SET
+                    title = excluded.title,
+                    date = excluded.date,
+                    status = excluded.status",
+                params![
+                    record.event_id.to_string(),
+                    record.guild_id.get() as i64,
+                    record.user_id.get() as i64,
+                    record.title,
+                    record.date.timestamp(),
+                    record.status.as_str()
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn attendance(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Vec<AttendanceRecord>, Error> {
+        let conn = self.conn.lock().unwrap();
+        let mut statement = conn.prepare(
+            "SELECT event_id, title, date, status FROM attendance
+            WHERE guild_id = ?1 AND user_id = ?2 ORDER BY date DESC",
+        )?;
+        let mut rows = statement.query([guild_id.get() as i64, user_id.get() as i64])?;
+        let mut records = Vec::new();
+        while let Some(row) = rows.next()? {
+            records.push(AttendanceRecord {
+                event_id: Uuid::parse_str(&row.get::<_, String>("event_id")?)?,
+                guild_id,
+                user_id,
+                title: row.get("title")?,
+                date: from_timestamp(row.get("date")?)?,
+                status: row
+                    .get::<_, String>("status")?
+                    .parse::<AttendanceStatus>()?,
+            });
+        }
+        Ok(records)
+    }
+
+    fn delete_series(&self, id: Uuid) -> Result<(), Error> {
+        // Removing the template cascades to the series row.
+        self.delete_event(id)
+    }
+}
@@ -0,0 +1,48 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use poise::serenity_prelude::{GuildId, UserId};
+use uuid::Uuid;
+
+/// Whether a participant showed up, decided once the event is over.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AttendanceStatus {
+    Attended,
+    Late,
+    NoShow,
+}
+
+impl AttendanceStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttendanceStatus::Attended => "attended",
+            AttendanceStatus::Late => "late",
+            AttendanceStatus::NoShow => "no-show",
+        }
+    }
+}
+
+impl FromStr for AttendanceStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "attended" => Ok(AttendanceStatus::Attended),
+            "late" => Ok(AttendanceStatus::Late),
+            "no-show" => Ok(AttendanceStatus::NoShow),
+            _ => Err(format!("Unknown attendance status {s}.")),
+        }
+    }
+}
+
+/// How one participant attended one event. Records outlive the event, so
+/// they carry its title and date.
+#[derive(Clone)]
+pub struct AttendanceRecord {
+    pub event_id: Uuid,
+    pub guild_id: GuildId,
+    pub user_id: UserId,
+    pub title: String,
+    pub date: DateTime<Utc>,
+    pub status: AttendanceStatus,
+}
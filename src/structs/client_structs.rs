@@ -1,6 +1,8 @@
+use std::sync::Arc;
+
 use poise::serenity_prelude::prelude::TypeMapKey;
 
-use super::event::Event;
+use crate::storage::EventStore;
 
 pub enum Command {
     CreateEvent,
@@ -9,10 +11,11 @@ pub enum Command {
 pub struct EventData;
 
 impl TypeMapKey for EventData {
-    type Value = Vec<Event>;
+    type Value = Arc<dyn EventStore>;
 }
 
 pub struct Data {}
 
 pub type Error = Box<dyn std::error::Error + Send + Sync>;
 pub type Context<'a> = poise::Context<'a, Data, Error>;
+pub type ApplicationContext<'a> = poise::ApplicationContext<'a, Data, Error>;
@@ -1,24 +1,72 @@
+use std::str::FromStr;
+
 use chrono::DateTime;
-use chrono::{self};
+use chrono::{self, Duration};
 use poise::serenity_prelude::{
-    self as serenity, CacheHttp, CreateEmbed, EditMessage, GuildId, Message,
+    self as serenity, CacheHttp, ChannelId, Color, CreateActionRow, CreateEmbed, CreateMessage,
+    EditMessage, GuildId, Message, MessageId, ScheduledEventId, UserId,
 };
+use poise::ChoiceParameter;
 use serenity::model::user::User;
+
+use super::attendance::{AttendanceRecord, AttendanceStatus};
+use super::event_button::EventButton;
+use super::event_error::EventError;
+use super::reminders::ReminderSettings;
 use uuid::Uuid;
 
 #[derive(Clone)]
 pub struct Event {
     pub creator: User,
     pub title: String,
+    pub description: String,
     pub id: Uuid,
-    server_id: GuildId,
-    date: DateTime<chrono::Utc>,
-    participants: Vec<Participant>,
-    needed_roles: Vec<Role>,
-    needed_flavors: Vec<RoleFlavor>,
-    event_messages: Vec<Message>,
+    pub(crate) server_id: GuildId,
+    pub(crate) date: DateTime<chrono::Utc>,
+    pub(crate) participants: Vec<Participant>,
+    /// Members waiting for a slot, in the order they signed up.
+    pub(crate) waitlist: Vec<Participant>,
+    pub(crate) needed_roles: Vec<Role>,
+    pub(crate) needed_flavors: Vec<RoleFlavor>,
+    pub(crate) event_messages: Vec<EventMessage>,
+    pub(crate) series: Option<Occurrence>,
+    pub reminders: ReminderSettings,
+    pub(crate) state: EventState,
+    pub(crate) cancel_reason: Option<String>,
+    pub(crate) duration: Duration,
+    /// Embed color while the event is open.
+    pub(crate) color: Color,
+    /// Members besides the creator who may manage the event.
+    pub(crate) co_organizers: Vec<UserId>,
+    /// Whether the event is mirrored as a Discord scheduled event.
+    pub(crate) native_event: bool,
+    /// The Discord scheduled event mirroring this one, once it was created.
+    pub(crate) scheduled_event: Option<ScheduledEventId>,
+    /// Whether the event gets a discussion thread on its embed.
+    pub(crate) own_thread: bool,
+    /// The discussion thread, once it was opened.
+    pub(crate) thread: Option<ChannelId>,
+    /// The channel created for the event alone, until it is cleaned up.
+    pub(crate) own_channel: Option<ChannelId>,
+    /// Whether a voice channel is opened for the participants before the start.
+    pub(crate) own_voice: bool,
+    /// Whether members on the waitlist may join the voice channel too.
+    pub(crate) voice_waitlist: bool,
+    /// The voice channel, while it exists.
+    pub(crate) voice: Option<ChannelId>,
+    /// Since when the voice channel has been empty after the event ended.
+    pub(crate) voice_empty_since: Option<DateTime<chrono::Utc>>,
+    /// Whether participants in voice are checked in without pressing the button.
+    pub(crate) auto_check_in: bool,
 }
 
+/// How long an event lasts unless its creator says otherwise.
+pub const DEFAULT_DURATION: Duration = Duration::hours(2);
+/// How long before the start participants can check in.
+pub const CHECK_IN_LEAD: Duration = Duration::minutes(15);
+/// How long after the start a check-in still counts as on time.
+pub const LATE_AFTER: Duration = Duration::minutes(5);
+
 impl Event {
     pub fn new(
         creator: User,
@@ -29,13 +77,32 @@ impl Event {
         Event {
             creator,
             title,
+            description: String::new(),
             date,
             server_id,
             id: Uuid::new_v4(),
             participants: Vec::new(),
+            waitlist: Vec::new(),
             needed_roles: Vec::new(),
             needed_flavors: Vec::new(),
             event_messages: Vec::new(),
+            series: None,
+            reminders: ReminderSettings::default(),
+            state: EventState::Open,
+            cancel_reason: None,
+            duration: DEFAULT_DURATION,
+            color: Color::PURPLE,
+            co_organizers: Vec::new(),
+            native_event: false,
+            scheduled_event: None,
+            own_thread: false,
+            thread: None,
+            own_channel: None,
+            own_voice: false,
+            voice_waitlist: false,
+            voice: None,
+            voice_empty_since: None,
+            auto_check_in: false,
         }
     }
 
@@ -47,6 +114,78 @@ impl Event {
         self.server_id
     }
 
+    pub fn date(&self) -> DateTime<chrono::Utc> {
+        self.date
+    }
+
+    /// Channel the event was first posted in.
+    pub fn channel_id(&self) -> Option<ChannelId> {
+        self.event_messages.first().map(|posted| posted.channel_id)
+    }
+
+    /// Link to the first posted embed of the event.
+    pub fn message_link(&self) -> Option<String> {
+        self.event_messages.first().map(|posted| {
+            posted
+                .message_id
+                .link(posted.channel_id, Some(self.server_id))
+        })
+    }
+
+    pub fn participants(&self) -> &[Participant] {
+        &self.participants
+    }
+
+    /// Whether the user created the event or was made one of its co-organizers.
+    pub fn is_organizer(&self, user_id: UserId) -> bool {
+        self.creator.id == user_id || self.co_organizers.contains(&user_id)
+    }
+
+    pub fn end(&self) -> DateTime<chrono::Utc> {
+        self.date + self.duration
+    }
+
+    pub fn state(&self) -> EventState {
+        self.state
+    }
+
+    /// Moves the event to `state` if its lifecycle allows it.
+    pub fn set_state(&mut self, state: EventState) -> Result<(), EventError> {
+        if !self.state.can_become(state) {
+            return Err(EventError::InvalidTransition {
+                from: self.state,
+                to: state,
+            });
+        }
+        self.state = state;
+        Ok(())
+    }
+
+    /// Marks the event as cancelled. The roster is kept so participants can
+    /// still be told about it.
+    pub fn cancel(&mut self, reason: Option<String>) -> Result<(), EventError> {
+        self.set_state(EventState::Cancelled)?;
+        self.cancel_reason = reason;
+        Ok(())
+    }
+
+    /// Starts and completes the event once its time has come, and cancels
+    /// drafts that were never opened by the time they would have ended.
+    /// Returns whether the state changed.
+    pub fn advance(&mut self, now: DateTime<chrono::Utc>) -> bool {
+        let before = self.state;
+        if self.state == EventState::Draft && self.end() <= now {
+            self.state = EventState::Cancelled;
+        }
+        if matches!(self.state, EventState::Open | EventState::Locked) && self.date <= now {
+            self.state = EventState::Started;
+        }
+        if self.state == EventState::Started && self.end() <= now {
+            self.state = EventState::Completed;
+        }
+        self.state != before
+    }
+
     pub fn add_role(&mut self, role: Role) {
         self.needed_roles.push(role);
     }
@@ -55,63 +194,219 @@ impl Event {
         self.needed_flavors.push(flavor);
     }
 
-    pub fn add_event_message(&mut self, new_message: Message) {
-        self.event_messages.push(new_message);
+    pub fn add_event_message(&mut self, message: &Message) {
+        self.event_messages.push(EventMessage {
+            channel_id: message.channel_id,
+            message_id: message.id,
+        });
     }
 
-    pub async fn update_event_messages(&mut self, http: &impl CacheHttp) {
-        let message = EditMessage::new().embed(
-            CreateEmbed::new()
-                .title(&self.title)
-                .description(self.build_new_message()),
-        );
-        for l in self
-            .event_messages
-            .iter_mut()
-            .map(|x| x.edit(http, message.clone()))
-        {
-            l.await.unwrap();
+    /// Posts a new embed for the event to `channel_id` and keeps track of it.
+    pub async fn post_message(
+        &mut self,
+        http: &impl CacheHttp,
+        channel_id: ChannelId,
+    ) -> serenity::Result<Message> {
+        let message = channel_id
+            .send_message(
+                http,
+                CreateMessage::new()
+                    .add_embed(self.embed())
+                    .components(self.components()),
+            )
+            .await?;
+        self.add_event_message(&message);
+        Ok(message)
+    }
+
+    pub fn embed(&self) -> CreateEmbed {
+        let (title, notice) = match self.state {
+            EventState::Open => (self.title.clone(), String::new()),
+            state => {
+                let reason = match (&self.cancel_reason, state) {
+                    (Some(reason), EventState::Cancelled) => format!("\nReason: {reason}"),
+                    _ => String::new(),
+                };
+                (
+                    format!("[{}] {}", state.name(), self.title),
+                    format!("**{}**{reason}\n\n", state.notice()),
+                )
+            }
+        };
+        CreateEmbed::new()
+            .title(title)
+            .description(format!(
+                "{notice}{}{}",
+                self.description,
+                self.build_new_message()
+            ))
+            .color(match self.state {
+                EventState::Open => self.color,
+                state => state.color(),
+            })
+    }
+
+    /// Buttons under the embed: answers while the event takes them and the
+    /// check-in until it is over.
+    pub fn components(&self) -> Vec<CreateActionRow> {
+        match self.state {
+            EventState::Open => vec![
+                EventButton::action_row(self.id),
+                EventButton::check_in_row(self.id),
+            ],
+            EventState::Locked | EventState::Started => vec![EventButton::check_in_row(self.id)],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Edits every posted embed to show the current roster. Returns the
+    /// messages that were deleted in Discord, see [`Event::forget_messages`].
+    pub async fn update_event_messages(&self, http: &impl CacheHttp) -> Vec<MessageId> {
+        let message = EditMessage::new()
+            .embed(self.embed())
+            .components(self.components());
+        let mut deleted = Vec::new();
+        for posted in self.event_messages.iter() {
+            let result = posted
+                .channel_id
+                .edit_message(http, posted.message_id, message.clone())
+                .await;
+            if let Err(serenity::Error::Http(err)) = result {
+                if err.status_code().is_some_and(|code| code.as_u16() == 404) {
+                    deleted.push(posted.message_id);
+                }
+            }
+        }
+        deleted
+    }
+
+    /// Drops posted embeds that no longer exist.
+    pub fn forget_messages(&mut self, deleted: &[MessageId]) {
+        self.event_messages
+            .retain(|posted| !deleted.contains(&posted.message_id));
+    }
+
+    /// Deletes every posted embed of the event.
+    pub async fn delete_event_messages(&mut self, http: &impl CacheHttp) {
+        for posted in self.event_messages.drain(..) {
+            // Already deleted messages are fine, nothing else to do about failures.
+            let _ = posted
+                .channel_id
+                .delete_message(http.http(), posted.message_id)
+                .await;
         }
     }
 
+    /// Fill level of the flavors, e.g. `Ranged 3/4, Melee 1/2+, Caster 2`.
+    fn flavor_summary(&self, flavors: &[&RoleFlavor]) -> String {
+        flavors
+            .iter()
+            .map(|flv| {
+                let count = self.flavor_count(flv);
+                match (flv.min, flv.max) {
+                    (0, None) => format!("{} {count}", flv.flavor),
+                    (0, Some(max)) => format!("{} {count}/{max}", flv.flavor),
+                    (min, None) => format!("{} {count}/{min}+", flv.flavor),
+                    (min, Some(max)) => format!("{} {count}/{min}-{max}", flv.flavor),
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
     pub fn build_new_message(&self) -> String {
         let mut role_strings = Vec::<String>::new();
         for role in self.needed_roles.iter() {
             let participant_iter = self
                 .participants
                 .iter()
-                .filter(|prt| prt.role.name == role.name);
+                .filter(|prt| prt.role.name == role.name && prt.status.takes_slot());
+            let role_flavors = self
+                .needed_flavors
+                .iter()
+                .filter(|flv| flv.role.as_ref() == Some(&role.name))
+                .collect::<Vec<&RoleFlavor>>();
             role_strings.push(format!(
-                "{}: {}/{}",
+                "{}: {}/{}{}",
                 role.name,
                 participant_iter.clone().count(),
-                role.amount
+                role.amount,
+                match role_flavors.is_empty() {
+                    true => String::new(),
+                    false => format!(" ({})", self.flavor_summary(&role_flavors)),
+                }
             ));
             for participant in participant_iter {
                 role_strings.push(format!(
-                    "→ {}{}",
-                    &participant
+                    "→ {}{}{}{}",
+                    participant
                         .flavor
-                        .clone()
-                        .unwrap_or(RoleFlavor {
-                            flavor: "".to_string(),
-                            amount: 0
-                        })
-                        .flavor,
-                    participant.id
+                        .as_ref()
+                        .map(|flv| format!("{} ", flv.flavor))
+                        .unwrap_or_default(),
+                    participant.id,
+                    match participant.status {
+                        RsvpStatus::Late => " (late)",
+                        _ => "",
+                    },
+                    match participant.checked_in {
+                        Some(_) => " ✅",
+                        None => "",
+                    }
                 ));
             }
+            let waiting = self
+                .waitlist
+                .iter()
+                .filter(|prt| prt.role.name == role.name)
+                .map(|prt| prt.id.to_string())
+                .collect::<Vec<String>>();
+            if !waiting.is_empty() {
+                role_strings.push(format!("Waitlist: {}", waiting.join(", ")));
+            }
+        }
+        let shared_flavors = self
+            .needed_flavors
+            .iter()
+            .filter(|flv| flv.role.is_none())
+            .collect::<Vec<&RoleFlavor>>();
+        if !shared_flavors.is_empty() {
+            role_strings.push(format!(
+                "\nFlavors: {}",
+                self.flavor_summary(&shared_flavors)
+            ));
+        }
+        for (status, heading) in [
+            (RsvpStatus::Tentative, "Tentative"),
+            (RsvpStatus::Declined, "Declined"),
+        ] {
+            let answered = self
+                .participants
+                .iter()
+                .filter(|prt| prt.status == status)
+                .map(|prt| match prt.role.is_none() {
+                    true => prt.id.to_string(),
+                    false => format!("{} ({})", prt.id, prt.role.name),
+                })
+                .collect::<Vec<String>>();
+            if !answered.is_empty() {
+                role_strings.push(format!("\n{heading}: {}", answered.join(", ")));
+            }
         }
 
         format!(
             "\n
-        Date: <t:{}>
+        Date: <t:{}> - <t:{}:t>
         ({} / {})
         Needed Roles:\n
         {}
         ",
             self.date.timestamp(),
-            self.participants.len(),
+            self.end().timestamp(),
+            self.participants
+                .iter()
+                .filter(|prt| prt.status.takes_slot())
+                .count(),
             self.needed_roles
                 .iter()
                 .map(|role| role.amount)
@@ -120,120 +415,558 @@ impl Event {
         )
     }
 
+    /// Signs the member up, or puts them on the waitlist if their role is
+    /// full. See [`Event::ensure_open`] for `as_organizer`.
     pub fn add_participant(
         &mut self,
+        as_organizer: bool,
         user: User,
         role: String,
         flavor: String,
-    ) -> Result<(), &str> {
-        let user_role = match self.needed_roles.iter().find(|rl| rl.name == role) {
-            Some(rl) => rl.clone(),
-            None => return Err("No role with that name found."),
-        };
-        let user_flavor = match self.needed_flavors.iter().find(|flv| flv.flavor == flavor) {
+        status: RsvpStatus,
+    ) -> Result<Placement, EventError> {
+        self.ensure_open(as_organizer)?;
+        if self.contains_participant(&user) && !self.declined_without_role(&user) {
+            return Err(EventError::AlreadySignedUp);
+        }
+        let user_role = self
+            .needed_roles
+            .iter()
+            .find(|rl| rl.name == role)
+            .cloned()
+            .ok_or(EventError::RoleNotFound(role))?;
+        let user_flavor = match self.find_flavor(&user_role.name, &flavor) {
             Some(flv) => Some(flv.clone()),
+            None if flavor.is_empty() => None,
+            None => return Err(EventError::FlavorNotFound(flavor)),
+        };
+        // Picking a role replaces an earlier decline without one.
+        self.participants.retain(|prt| prt.id.id != user.id);
+        let participant = Participant {
+            id: user,
+            role: user_role,
+            flavor: user_flavor,
+            status,
+            checked_in: None,
+        };
+        if !self.has_room_for(&participant) {
+            self.waitlist.push(participant);
+            return Ok(Placement::Waitlist);
+        }
+        self.participants.push(participant);
+        Ok(Placement::Roster)
+    }
+
+    /// Records that a member who has not answered yet won't come, without
+    /// making them pick a role.
+    pub fn decline(&mut self, as_organizer: bool, user: User) -> Result<(), EventError> {
+        self.ensure_open(as_organizer)?;
+        match self.status_of(&user) {
+            Some(RsvpStatus::Declined) => Err(EventError::AlreadyAnswered(RsvpStatus::Declined)),
+            Some(_) => Err(EventError::AlreadySignedUp),
             None => {
-                if !flavor.is_empty() {
-                    return Err("No flavor with that name found.");
-                }
-                None
+                self.participants.push(Participant {
+                    id: user,
+                    role: Role::none(),
+                    flavor: None,
+                    status: RsvpStatus::Declined,
+                    checked_in: None,
+                });
+                Ok(())
+            }
+        }
+    }
+
+    /// Whether the member's only answer is a decline without a role, so
+    /// answering anything else needs a role first.
+    pub fn declined_without_role(&self, user: &User) -> bool {
+        self.participants
+            .iter()
+            .any(|prt| prt.id.id == user.id && prt.role.is_none())
+    }
+
+    /// Changes the RSVP of a member who already answered. Switching to a
+    /// status that needs a slot may put them on the waitlist, switching away
+    /// from one frees the slot for the waitlist. Returns where the member
+    /// ended up and everyone who was promoted.
+    pub fn set_status(
+        &mut self,
+        as_organizer: bool,
+        user: &User,
+        status: RsvpStatus,
+    ) -> Result<(Placement, Vec<Participant>), EventError> {
+        self.ensure_open(as_organizer)?;
+        match self.status_of(user) {
+            None => return Err(EventError::NotSignedUp),
+            Some(current) if current == status => {
+                return Err(EventError::AlreadyAnswered(status));
             }
+            Some(_) => {}
+        }
+        let mut participant =
+            if let Some(index) = self.waitlist.iter().position(|x| x.id.id == user.id) {
+                self.waitlist.remove(index)
+            } else {
+                let index = self
+                    .participants
+                    .iter()
+                    .position(|x| x.id.id == user.id)
+                    .ok_or(EventError::NotSignedUp)?;
+                self.participants.remove(index)
+            };
+        participant.status = status;
+        let placement = if self.has_room_for(&participant) {
+            self.participants.push(participant);
+            Placement::Roster
+        } else {
+            self.waitlist.push(participant);
+            Placement::Waitlist
         };
-        if self.is_full() {
-            return Err("Event is full.");
+        Ok((placement, self.promote_waitlisted()))
+    }
+
+    /// The RSVP the member gave, if any.
+    pub fn status_of(&self, user: &User) -> Option<RsvpStatus> {
+        self.participants
+            .iter()
+            .chain(self.waitlist.iter())
+            .find(|x| x.id.id == user.id)
+            .map(|x| x.status)
+    }
+
+    /// Replaces the roles and flavors of the event and fits the existing
+    /// answers into them. Members whose role no longer exists are signed off,
+    /// members who no longer fit are moved to the front of the waitlist and
+    /// waitlisted members who fit now are promoted.
+    pub fn update_roles(&mut self, roles: Vec<Role>, flavors: Vec<RoleFlavor>) -> RosterChanges {
+        self.needed_roles = roles;
+        self.needed_flavors = flavors;
+        let mut changes = RosterChanges::default();
+        let mut overflow = Vec::new();
+        let roster = std::mem::take(&mut self.participants);
+        let waiting = std::mem::take(&mut self.waitlist);
+        for (mut participant, waitlisted) in roster
+            .into_iter()
+            .map(|prt| (prt, false))
+            .chain(waiting.into_iter().map(|prt| (prt, true)))
+        {
+            if participant.role.is_none() {
+                self.participants.push(participant);
+                continue;
+            }
+            let Some(role) = self
+                .needed_roles
+                .iter()
+                .find(|rl| rl.name == participant.role.name)
+            else {
+                changes.removed.push(participant);
+                continue;
+            };
+            participant.role = role.clone();
+            participant.flavor = participant
+                .flavor
+                .and_then(|flv| self.find_flavor(&role.name, &flv.flavor).cloned());
+            if waitlisted {
+                self.waitlist.push(participant);
+            } else if self.has_room_for(&participant) {
+                self.participants.push(participant);
+            } else {
+                overflow.push(participant);
+            }
         }
-        if self.is_role_full(&user_role.name) {
-            return Err("Role is full.");
+        changes.waitlisted = overflow.clone();
+        overflow.append(&mut self.waitlist);
+        self.waitlist = overflow;
+        changes.promoted = self.promote_waitlisted();
+        changes
+    }
+
+    /// Tentative and declined answers never take a slot, so they always fit.
+    /// Others need a free slot in their role and flavor that is not held
+    /// back for a flavor still short of its minimum.
+    fn has_room_for(&self, participant: &Participant) -> bool {
+        if !participant.status.takes_slot() {
+            return true;
         }
-        if let Some(flv) = &user_flavor {
-            if self.is_flavor_full(&flv.flavor) {
-                return Err("Flavor is full.");
+        let role = &participant.role;
+        let flavor = participant.flavor.as_ref();
+        if self.is_full()
+            || self.is_role_full(&role.name)
+            || flavor.is_some_and(|flv| self.is_flavor_full(flv))
+        {
+            return false;
+        }
+        let held_back = |scoped: bool| {
+            self.needed_flavors
+                .iter()
+                .filter(|flv| flv.role.is_some() == scoped && flv.applies_to(&role.name))
+                .filter(|flv| !flavor.is_some_and(|own| own.is_same(flv)))
+                .map(|flv| flv.min.saturating_sub(self.flavor_count(flv)))
+                .sum::<u32>()
+        };
+        let taken = |role: Option<&str>| {
+            self.participants
+                .iter()
+                .filter(|x| x.status.takes_slot())
+                .filter(|x| role.is_none_or(|rl| x.role.name == rl))
+                .count() as u32
+        };
+        role.amount - taken(Some(&role.name)) > held_back(true)
+            && self.needed_participants() - taken(None) > held_back(false)
+    }
+
+    /// Moves waitlisted members onto the roster, first come first served, as
+    /// long as there are free slots for them. Returns everyone who was moved.
+    fn promote_waitlisted(&mut self) -> Vec<Participant> {
+        let mut promoted = Vec::new();
+        let mut index = 0;
+        while index < self.waitlist.len() {
+            if self.has_room_for(&self.waitlist[index]) {
+                let participant = self.waitlist.remove(index);
+                self.participants.push(participant.clone());
+                promoted.push(participant);
+            } else {
+                index += 1;
             }
         }
-        self.participants.push(Participant {
-            id: user,
-            role: user_role,
-            flavor: user_flavor,
-        });
-        Ok(())
+        promoted
     }
 
     fn is_full(&self) -> bool {
-        self.participants.len() == self.needed_roles.iter().map(|x| x.amount).sum::<u32>() as usize
+        self.participants
+            .iter()
+            .filter(|x| x.status.takes_slot())
+            .count()
+            >= self.needed_participants() as usize
     }
 
     pub fn is_role_full(&self, role: &String) -> bool {
         self.participants
             .iter()
-            .filter(|x| x.role.name == *role)
+            .filter(|x| x.role.name == *role && x.status.takes_slot())
             .count() as u32
             >= self
                 .needed_roles
                 .iter()
                 .find(|x| x.name == *role)
-                .unwrap()
-                .amount
+                .map_or(0, |x| x.amount)
     }
 
-    pub fn is_flavor_full(&self, flavor: &String) -> bool {
-        let flavor = self
-            .needed_flavors
+    pub fn is_flavor_full(&self, flavor: &RoleFlavor) -> bool {
+        flavor
+            .max
+            .is_some_and(|max| self.flavor_count(flavor) >= max)
+    }
+
+    /// Members on the roster who picked the flavor and take a slot.
+    fn flavor_count(&self, flavor: &RoleFlavor) -> u32 {
+        self.participants
             .iter()
-            .find(|x| x.flavor == *flavor)
-            .cloned()
-            .unwrap_or(RoleFlavor {
-                flavor: "".to_string(),
-                amount: 0,
-            });
-        flavor.amount == 0
-            || self
-                .participants
-                .iter()
-                .filter(|x| {
-                    x.flavor
-                        .clone()
-                        .unwrap_or(RoleFlavor {
-                            flavor: "".to_string(),
-                            amount: 0,
-                        })
-                        .flavor
-                        == flavor.flavor
-                })
-                .count() as u32
-                >= flavor.amount
+            .filter(|x| x.status.takes_slot())
+            .filter(|x| x.flavor.as_ref().is_some_and(|flv| flv.is_same(flavor)))
+            .count() as u32
+    }
+
+    /// The flavor called `name` that members signing up as `role` may pick.
+    pub fn find_flavor(&self, role: &str, name: &str) -> Option<&RoleFlavor> {
+        self.needed_flavors
+            .iter()
+            .find(|flv| flv.flavor == name && flv.applies_to(role))
     }
 
-    pub fn remove_participant(&mut self, user: User) -> Result<(), &str> {
+    /// Removes the member from the roster or the waitlist. Returns everyone
+    /// who was promoted from the waitlist into the freed slot.
+    pub fn remove_participant(
+        &mut self,
+        as_organizer: bool,
+        user: User,
+    ) -> Result<Vec<Participant>, EventError> {
+        self.ensure_open(as_organizer)?;
+        if let Some(index) = self.waitlist.iter().position(|x| x.id.id == user.id) {
+            self.waitlist.remove(index);
+            return Ok(Vec::new());
+        }
         let index = self
             .participants
             .iter()
             .position(|x| x.id.id == user.id)
-            .ok_or("User not found.")?;
+            .ok_or(EventError::NotSignedUp)?;
         self.participants.remove(index);
+        Ok(self.promote_waitlisted())
+    }
+
+    /// Fails unless members can currently sign up, change their answer or
+    /// sign off. A locked roster can still be changed `as_organizer`, i.e. by
+    /// someone who manages the event on behalf of the member.
+    fn ensure_open(&self, as_organizer: bool) -> Result<(), EventError> {
+        match self.state {
+            EventState::Open => Ok(()),
+            EventState::Locked if as_organizer => Ok(()),
+            state => Err(EventError::NotOpen(state)),
+        }
+    }
+
+    /// Whether the member signed up, possibly on the waitlist, and did not decline.
+    pub fn attends(&self, user_id: UserId) -> bool {
+        self.participants
+            .iter()
+            .chain(self.waitlist.iter())
+            .any(|x| x.id.id == user_id && x.status != RsvpStatus::Declined)
+    }
+
+    /// Marks the participant as present. Only members on the roster can check
+    /// in, from [`CHECK_IN_LEAD`] before the start until the end.
+    pub fn check_in(
+        &mut self,
+        user_id: UserId,
+        now: DateTime<chrono::Utc>,
+    ) -> Result<(), EventError> {
+        if !matches!(
+            self.state,
+            EventState::Open | EventState::Locked | EventState::Started
+        ) || now >= self.end()
+        {
+            return Err(EventError::CheckInClosed);
+        }
+        if now < self.date - CHECK_IN_LEAD {
+            return Err(EventError::CheckInNotYet(self.date - CHECK_IN_LEAD));
+        }
+        let participant = self
+            .participants
+            .iter_mut()
+            .find(|prt| prt.id.id == user_id && prt.status != RsvpStatus::Declined)
+            .ok_or(EventError::NotSignedUp)?;
+        if participant.checked_in.is_some() {
+            return Err(EventError::AlreadyCheckedIn);
+        }
+        participant.checked_in = Some(now);
         Ok(())
     }
 
+    /// How every participant who held a slot attended, judged by their
+    /// check-in. Tentative and declined answers leave no record.
+    pub fn attendance(&self) -> Vec<AttendanceRecord> {
+        self.participants
+            .iter()
+            .filter(|prt| prt.status.takes_slot())
+            .map(|prt| AttendanceRecord {
+                event_id: self.id,
+                guild_id: self.server_id,
+                user_id: prt.id.id,
+                title: self.title.clone(),
+                date: self.date,
+                status: match prt.checked_in {
+                    Some(at) if at <= self.date + LATE_AFTER => AttendanceStatus::Attended,
+                    Some(_) => AttendanceStatus::Late,
+                    None => AttendanceStatus::NoShow,
+                },
+            })
+            .collect()
+    }
+
+    /// Whether the member is on the roster or the waitlist.
     pub fn contains_participant(&self, user: &User) -> bool {
-        self.participants.iter().any(|x| x.id.id == user.id)
+        self.participants
+            .iter()
+            .chain(self.waitlist.iter())
+            .any(|x| x.id.id == user.id)
+    }
+
+    /// Role name as offered in the sign-up menus.
+    pub fn role_label(&self, role: &String) -> String {
+        if self.is_role_full(role) {
+            format!("{role} (waitlist)")
+        } else {
+            role.clone()
+        }
+    }
+
+    /// Flavor name as offered in the sign-up menus for `role`.
+    pub fn flavor_label(&self, role: &str, flavor: &String) -> String {
+        let Some(flv) = self.find_flavor(role, flavor) else {
+            return flavor.clone();
+        };
+        let missing = flv.min.saturating_sub(self.flavor_count(flv));
+        if self.is_flavor_full(flv) {
+            format!("{flavor} (waitlist)")
+        } else if missing > 0 {
+            format!("{flavor} ({missing} needed)")
+        } else {
+            flavor.clone()
+        }
     }
 
     pub fn roles(&self) -> Vec<String> {
         self.needed_roles.iter().map(|x| x.name.clone()).collect()
     }
 
-    pub fn flavors(&self) -> Vec<String> {
+    /// Flavors members signing up as `role` may pick.
+    pub fn flavors(&self, role: &str) -> Vec<String> {
         self.needed_flavors
             .iter()
+            .filter(|x| x.applies_to(role))
             .map(|x| x.flavor.clone())
             .collect()
     }
 }
 
+/// Lifecycle of an event. Transitions are checked by [`Event::set_state`].
+#[derive(Clone, Copy, Debug, PartialEq, poise::ChoiceParameter)]
+pub enum EventState {
+    /// Posted, but not taking sign-ups yet.
+    Draft,
+    Open,
+    /// The roster is frozen.
+    Locked,
+    Started,
+    Completed,
+    Cancelled,
+}
+
+impl EventState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventState::Draft => "draft",
+            EventState::Open => "open",
+            EventState::Locked => "locked",
+            EventState::Started => "started",
+            EventState::Completed => "completed",
+            EventState::Cancelled => "cancelled",
+        }
+    }
+
+    fn can_become(&self, next: EventState) -> bool {
+        use EventState::*;
+        matches!(
+            (*self, next),
+            (Draft, Open)
+                | (Open, Locked)
+                | (Locked, Open)
+                | (Open | Locked, Started)
+                | (Started, Completed)
+                | (Draft | Open | Locked | Started, Cancelled)
+        )
+    }
+
+    /// Why the roster can't be changed in this state.
+    pub fn notice(&self) -> &'static str {
+        match self {
+            EventState::Draft => "Sign-ups have not opened yet.",
+            EventState::Open => "Sign-ups are open.",
+            EventState::Locked => "The roster is locked. Ask an organizer to change it.",
+            EventState::Started => "The event has started.",
+            EventState::Completed => "The event is over.",
+            EventState::Cancelled => "This event has been cancelled.",
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            EventState::Draft => Color::LIGHT_GREY,
+            EventState::Open => Color::PURPLE,
+            EventState::Locked => Color::ORANGE,
+            EventState::Started => Color::BLUE,
+            EventState::Completed => Color::DARK_GREEN,
+            EventState::Cancelled => Color::DARK_GREY,
+        }
+    }
+}
+
+impl FromStr for EventState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "draft" => Ok(EventState::Draft),
+            "open" => Ok(EventState::Open),
+            "locked" => Ok(EventState::Locked),
+            "started" => Ok(EventState::Started),
+            "completed" => Ok(EventState::Completed),
+            "cancelled" => Ok(EventState::Cancelled),
+            _ => Err(format!("Unknown event state {s}.")),
+        }
+    }
+}
+
+/// Where a new sign-up ended up.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Placement {
+    Roster,
+    Waitlist,
+}
+
+/// Members affected by [`Event::update_roles`].
+#[derive(Default)]
+pub struct RosterChanges {
+    /// Signed off because their role was removed.
+    pub removed: Vec<Participant>,
+    /// Moved from the roster to the waitlist.
+    pub waitlisted: Vec<Participant>,
+    /// Moved from the waitlist to the roster.
+    pub promoted: Vec<Participant>,
+}
+
+/// Reference to an embed posted for an event.
+#[derive(Clone)]
+pub struct EventMessage {
+    pub channel_id: ChannelId,
+    pub message_id: MessageId,
+}
+
+/// Links an event to the recurring series it was created from.
+#[derive(Clone)]
+pub struct Occurrence {
+    pub series_id: Uuid,
+    /// Start time given by the recurrence rule, before any per-occurrence edits.
+    pub scheduled: DateTime<chrono::Utc>,
+}
+
 #[derive(Clone)]
 pub struct Participant {
     pub id: User,
     pub role: Role,
     pub flavor: Option<RoleFlavor>,
+    pub status: RsvpStatus,
+    pub checked_in: Option<DateTime<chrono::Utc>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RsvpStatus {
+    Accepted,
+    Tentative,
+    Declined,
+    /// Coming, but after the start.
+    Late,
+}
+
+impl RsvpStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RsvpStatus::Accepted => "accepted",
+            RsvpStatus::Tentative => "tentative",
+            RsvpStatus::Declined => "declined",
+            RsvpStatus::Late => "late",
+        }
+    }
+
+    /// Whether the answer counts toward the capacity of the event.
+    pub fn takes_slot(&self) -> bool {
+        matches!(self, RsvpStatus::Accepted | RsvpStatus::Late)
+    }
+}
+
+impl FromStr for RsvpStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "accepted" => Ok(RsvpStatus::Accepted),
+            "tentative" => Ok(RsvpStatus::Tentative),
+            "declined" => Ok(RsvpStatus::Declined),
+            "late" => Ok(RsvpStatus::Late),
+            _ => Err(format!("Unknown RSVP status {s}.")),
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -242,8 +975,261 @@ pub struct Role {
     pub amount: u32,
 }
 
+impl Role {
+    /// Role of members who declined without picking one.
+    pub fn none() -> Role {
+        Role {
+            name: String::new(),
+            amount: 0,
+        }
+    }
+
+    pub fn is_none(&self) -> bool {
+        self.name.is_empty()
+    }
+}
+
 #[derive(Clone)]
 pub struct RoleFlavor {
     pub flavor: String,
-    pub amount: u32,
+    /// Role the flavor belongs to. Flavors without one are open to every role.
+    pub role: Option<String>,
+    /// Members the flavor needs. Slots are held back for it until it has them.
+    pub min: u32,
+    /// Members the flavor takes at most, any number if `None`.
+    pub max: Option<u32>,
+}
+
+impl RoleFlavor {
+    /// Whether members signing up as `role` may pick the flavor.
+    pub fn applies_to(&self, role: &str) -> bool {
+        self.role.as_deref().is_none_or(|rl| rl == role)
+    }
+
+    fn is_same(&self, other: &RoleFlavor) -> bool {
+        self.flavor == other.flavor && self.role == other.role
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::event_parser::{parse_flavors, parse_roles};
+
+    fn user(id: u64) -> User {
+        let mut user = User::default();
+        user.id = UserId::new(id);
+        user
+    }
+
+    fn event(roles: &str, flavors: &str) -> Event {
+        let mut event = Event::new(
+            user(1),
+            String::from("Raid"),
+            chrono::Utc::now(),
+            GuildId::new(1),
+        );
+        event.needed_roles = parse_roles(roles).unwrap();
+        event.needed_flavors = parse_flavors(flavors).unwrap();
+        event
+    }
+
+    fn sign_up(
+        event: &mut Event,
+        id: u64,
+        role: &str,
+        flavor: &str,
+        status: RsvpStatus,
+    ) -> Placement {
+        event
+            .add_participant(
+                false,
+                user(id),
+                role.to_string(),
+                flavor.to_string(),
+                status,
+            )
+            .unwrap()
+    }
+
+    fn ids(participants: &[Participant]) -> Vec<u64> {
+        participants.iter().map(|prt| prt.id.id.get()).collect()
+    }
+
+    #[test]
+    fn minimums_hold_back_slots() {
+        let mut event = event("DPS:3", "DPS/Melee:1+, DPS/Ranged");
+        assert_eq!(
+            sign_up(&mut event, 2, "DPS", "Ranged", RsvpStatus::Accepted),
+            Placement::Roster
+        );
+        assert_eq!(
+            sign_up(&mut event, 3, "DPS", "", RsvpStatus::Accepted),
+            Placement::Roster
+        );
+        // The last slot is kept for a melee.
+        assert_eq!(
+            sign_up(&mut event, 4, "DPS", "Ranged", RsvpStatus::Accepted),
+            Placement::Waitlist
+        );
+        assert_eq!(
+            sign_up(&mut event, 5, "DPS", "Melee", RsvpStatus::Accepted),
+            Placement::Roster
+        );
+    }
+
+    #[test]
+    fn minimums_of_flavors_for_every_role_hold_back_slots() {
+        let mut event = event("Tank:1, DPS:2", "Leader:1+");
+        assert_eq!(
+            sign_up(&mut event, 2, "DPS", "", RsvpStatus::Accepted),
+            Placement::Roster
+        );
+        assert_eq!(
+            sign_up(&mut event, 3, "Tank", "", RsvpStatus::Accepted),
+            Placement::Roster
+        );
+        assert_eq!(
+            sign_up(&mut event, 4, "DPS", "", RsvpStatus::Accepted),
+            Placement::Waitlist
+        );
+        assert_eq!(
+            sign_up(&mut event, 5, "DPS", "Leader", RsvpStatus::Accepted),
+            Placement::Roster
+        );
+    }
+
+    #[test]
+    fn tentative_answers_take_no_slot() {
+        let mut event = event("DPS:1", "");
+        assert_eq!(
+            sign_up(&mut event, 2, "DPS", "", RsvpStatus::Tentative),
+            Placement::Roster
+        );
+        assert_eq!(
+            sign_up(&mut event, 3, "DPS", "", RsvpStatus::Accepted),
+            Placement::Roster
+        );
+        assert_eq!(
+            sign_up(&mut event, 4, "DPS", "", RsvpStatus::Accepted),
+            Placement::Waitlist
+        );
+        // Switching to accepted needs a slot like signing up does.
+        let (placement, promoted) = event
+            .set_status(false, &user(2), RsvpStatus::Accepted)
+            .unwrap();
+        assert_eq!(placement, Placement::Waitlist);
+        assert!(promoted.is_empty());
+    }
+
+    #[test]
+    fn promotes_the_waitlist_in_order() {
+        let mut event = event("Tank:1, DPS:1", "");
+        sign_up(&mut event, 2, "DPS", "", RsvpStatus::Accepted);
+        sign_up(&mut event, 3, "Tank", "", RsvpStatus::Accepted);
+        sign_up(&mut event, 4, "Tank", "", RsvpStatus::Accepted);
+        sign_up(&mut event, 5, "DPS", "", RsvpStatus::Accepted);
+        sign_up(&mut event, 6, "DPS", "", RsvpStatus::Accepted);
+        let (_, promoted) = event
+            .set_status(false, &user(2), RsvpStatus::Declined)
+            .unwrap();
+        // The waiting tank can't take the DPS slot, so the first DPS gets it.
+        assert_eq!(ids(&promoted), vec![5]);
+        assert_eq!(ids(&event.waitlist), vec![4, 6]);
+    }
+
+    #[test]
+    fn shrinking_a_role_moves_the_overflow_to_the_front_of_the_waitlist() {
+        let mut event = event("Tank:1, DPS:3", "");
+        for id in 2..=5 {
+            sign_up(&mut event, id, "DPS", "", RsvpStatus::Accepted);
+        }
+        sign_up(&mut event, 6, "Tank", "", RsvpStatus::Accepted);
+        let changes = event.update_roles(parse_roles("DPS:1").unwrap(), Vec::new());
+        assert_eq!(ids(&changes.removed), vec![6]);
+        assert_eq!(ids(&changes.waitlisted), vec![3, 4]);
+        assert!(changes.promoted.is_empty());
+        assert_eq!(ids(&event.participants), vec![2]);
+        assert_eq!(ids(&event.waitlist), vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn only_organizers_change_a_locked_roster() {
+        let mut event = event("DPS:2", "");
+        sign_up(&mut event, 2, "DPS", "", RsvpStatus::Accepted);
+        event.set_state(EventState::Locked).unwrap();
+        assert!(event
+            .add_participant(
+                false,
+                user(3),
+                "DPS".into(),
+                String::new(),
+                RsvpStatus::Accepted
+            )
+            .is_err());
+        assert!(event.remove_participant(false, user(2)).is_err());
+        assert!(event
+            .add_participant(
+                true,
+                user(3),
+                "DPS".into(),
+                String::new(),
+                RsvpStatus::Accepted
+            )
+            .is_ok());
+        assert!(event.remove_participant(true, user(2)).is_ok());
+    }
+
+    #[test]
+    fn declining_needs_no_role() {
+        let mut event = event("DPS:1", "");
+        event.decline(false, user(2)).unwrap();
+        assert!(event.declined_without_role(&user(2)));
+        assert!(event.decline(false, user(2)).is_err());
+        // The decline keeps its place through role changes and takes no slot.
+        event.update_roles(parse_roles("Tank:1").unwrap(), Vec::new());
+        assert_eq!(
+            sign_up(&mut event, 3, "Tank", "", RsvpStatus::Accepted),
+            Placement::Roster
+        );
+        // Picking a role later replaces the decline.
+        assert_eq!(
+            sign_up(&mut event, 2, "Tank", "", RsvpStatus::Accepted),
+            Placement::Waitlist
+        );
+        assert_eq!(ids(&event.participants), vec![3]);
+        assert!(!event.declined_without_role(&user(2)));
+    }
+
+    #[test]
+    fn attendance_skips_tentative_answers() {
+        let mut event = event("DPS:2", "");
+        sign_up(&mut event, 2, "DPS", "", RsvpStatus::Accepted);
+        sign_up(&mut event, 3, "DPS", "", RsvpStatus::Tentative);
+        let records = event.attendance();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].user_id, UserId::new(2));
+        assert_eq!(records[0].status, AttendanceStatus::NoShow);
+    }
+
+    #[test]
+    fn drafts_expire_once_they_would_have_ended() {
+        let mut event = event("DPS:2", "");
+        event.state = EventState::Draft;
+        assert!(!event.advance(event.date));
+        assert_eq!(event.state(), EventState::Draft);
+        assert!(event.advance(event.end()));
+        assert_eq!(event.state(), EventState::Cancelled);
+    }
+
+    #[test]
+    fn growing_a_role_promotes_the_waitlist() {
+        let mut event = event("DPS:1", "");
+        for id in 2..=4 {
+            sign_up(&mut event, id, "DPS", "", RsvpStatus::Accepted);
+        }
+        let changes = event.update_roles(parse_roles("DPS:2").unwrap(), Vec::new());
+        assert_eq!(ids(&changes.promoted), vec![3]);
+        assert_eq!(ids(&event.waitlist), vec![4]);
+    }
 }
@@ -0,0 +1,80 @@
+use poise::serenity_prelude::{ButtonStyle, CreateActionRow, CreateButton};
+use uuid::Uuid;
+
+/// Buttons attached to every posted event embed. The custom id carries the
+/// event id, so clicks can still be routed after a restart.
+#[derive(Clone, Copy, PartialEq)]
+pub enum EventButton {
+    SignUp,
+    Tentative,
+    Late,
+    Decline,
+    SignOff,
+    CheckIn,
+}
+
+impl EventButton {
+    const PREFIX: &'static str = "event";
+
+    fn key(&self) -> &'static str {
+        match self {
+            EventButton::SignUp => "sign_up",
+            EventButton::Tentative => "tentative",
+            EventButton::Late => "late",
+            EventButton::Decline => "decline",
+            EventButton::SignOff => "sign_off",
+            EventButton::CheckIn => "check_in",
+        }
+    }
+
+    pub fn custom_id(&self, event_id: Uuid) -> String {
+        format!("{}:{}:{}", Self::PREFIX, self.key(), event_id)
+    }
+
+    /// Splits a custom id produced by [`EventButton::custom_id`] back into its parts.
+    pub fn parse(custom_id: &str) -> Option<(EventButton, Uuid)> {
+        let mut parts = custom_id.splitn(3, ':');
+        if parts.next()? != Self::PREFIX {
+            return None;
+        }
+        let button = match parts.next()? {
+            "sign_up" => EventButton::SignUp,
+            "tentative" => EventButton::Tentative,
+            "late" => EventButton::Late,
+            "decline" => EventButton::Decline,
+            "sign_off" => EventButton::SignOff,
+            "check_in" => EventButton::CheckIn,
+            _ => return None,
+        };
+        let event_id = Uuid::parse_str(parts.next()?).ok()?;
+        Some((button, event_id))
+    }
+
+    pub fn action_row(event_id: Uuid) -> CreateActionRow {
+        CreateActionRow::Buttons(vec![
+            CreateButton::new(EventButton::SignUp.custom_id(event_id))
+                .label("Sign up")
+                .style(ButtonStyle::Success),
+            CreateButton::new(EventButton::Tentative.custom_id(event_id))
+                .label("Tentative")
+                .style(ButtonStyle::Primary),
+            CreateButton::new(EventButton::Late.custom_id(event_id))
+                .label("Late")
+                .style(ButtonStyle::Primary),
+            CreateButton::new(EventButton::Decline.custom_id(event_id))
+                .label("Decline")
+                .style(ButtonStyle::Secondary),
+            CreateButton::new(EventButton::SignOff.custom_id(event_id))
+                .label("Sign off")
+                .style(ButtonStyle::Danger),
+        ])
+    }
+
+    pub fn check_in_row(event_id: Uuid) -> CreateActionRow {
+        CreateActionRow::Buttons(vec![CreateButton::new(
+            EventButton::CheckIn.custom_id(event_id),
+        )
+        .label("Check in")
+        .style(ButtonStyle::Success)])
+    }
+}
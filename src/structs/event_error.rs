@@ -0,0 +1,58 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+
+use super::event::{EventState, RsvpStatus};
+
+/// Why a change to an event or its roster was refused. The messages are
+/// meant for the member who tried it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventError {
+    NotFound,
+    /// The roster can't change in the given state.
+    NotOpen(EventState),
+    InvalidTransition {
+        from: EventState,
+        to: EventState,
+    },
+    RoleNotFound(String),
+    FlavorNotFound(String),
+    AlreadySignedUp,
+    NotSignedUp,
+    AlreadyAnswered(RsvpStatus),
+    /// Check-in opens at the given time.
+    CheckInNotYet(DateTime<Utc>),
+    CheckInClosed,
+    AlreadyCheckedIn,
+}
+
+impl fmt::Display for EventError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventError::NotFound => write!(f, "This event no longer exists."),
+            EventError::NotOpen(state) => write!(f, "{}", state.notice()),
+            EventError::InvalidTransition { from, to } => write!(
+                f,
+                "A {} event can't be moved to {}.",
+                from.as_str(),
+                to.as_str()
+            ),
+            EventError::RoleNotFound(role) => write!(f, "The event has no role called {role}."),
+            EventError::FlavorNotFound(flavor) => {
+                write!(f, "The event has no flavor called {flavor}.")
+            }
+            EventError::AlreadySignedUp => write!(f, "You are already signed up for this event."),
+            EventError::NotSignedUp => write!(f, "You are not signed up for this event."),
+            EventError::AlreadyAnswered(status) => {
+                write!(f, "You already answered {}.", status.as_str())
+            }
+            EventError::CheckInNotYet(opens) => {
+                write!(f, "Check-in opens <t:{}:R>.", opens.timestamp())
+            }
+            EventError::CheckInClosed => write!(f, "Check-in for this event is closed."),
+            EventError::AlreadyCheckedIn => write!(f, "You are already checked in."),
+        }
+    }
+}
+
+impl std::error::Error for EventError {}
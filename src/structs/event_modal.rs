@@ -0,0 +1,84 @@
+use chrono::Utc;
+use chrono_tz::Tz;
+use poise::serenity_prelude::{GuildId, User};
+
+use super::event::Event;
+use super::template::EventTemplate;
+use crate::util::date_parser::parse_date;
+use crate::util::event_parser::{
+    check_flavors, format_flavors, format_roles, parse_flavors, parse_roles,
+};
+
+#[derive(Clone, poise::Modal)]
+#[name = "Create an Event"]
+pub struct EventModal {
+    #[name = "Title"]
+    #[max_length = 100]
+    pub title: String,
+    #[name = "Description"]
+    #[paragraph]
+    pub description: Option<String>,
+    #[name = "Date and time"]
+    #[placeholder = "2026-11-03 19:30, next friday 8pm, tomorrow 21:15 CET"]
+    pub date: String,
+    #[name = "Roles"]
+    #[placeholder = "Tank:2, Healer:2, DPS:6"]
+    pub roles: String,
+    #[name = "Flavors"]
+    #[placeholder = "Ranged:4, DPS/Melee:2+, Healer/Holy:1-2"]
+    pub flavors: Option<String>,
+}
+
+impl EventModal {
+    /// A form with the description, roles and flavors of the template filled in.
+    pub fn from_template(template: &EventTemplate) -> EventModal {
+        let flavors = format_flavors(&template.flavors);
+        EventModal {
+            title: String::new(),
+            description: Some(template.description.clone()).filter(|desc| !desc.is_empty()),
+            date: String::new(),
+            roles: format_roles(&template.roles),
+            flavors: Some(flavors).filter(|flavors| !flavors.is_empty()),
+        }
+    }
+
+    /// Builds the event described by the form, or every problem found in it.
+    /// Dates without a zone of their own are read in `zone`.
+    pub fn to_event(
+        &self,
+        creator: User,
+        server_id: GuildId,
+        zone: Tz,
+    ) -> Result<Event, Vec<String>> {
+        let mut errors = Vec::new();
+        let date = parse_date(&self.date, zone, Utc::now())
+            .map_err(|err| errors.push(err))
+            .ok();
+        let roles = parse_roles(&self.roles)
+            .map_err(|err| errors.push(err))
+            .ok();
+        let flavors = parse_flavors(self.flavors.as_deref().unwrap_or_default())
+            .map_err(|err| errors.push(err))
+            .ok();
+        if let (Some(roles), Some(flavors)) = (&roles, &flavors) {
+            if let Err(err) = check_flavors(roles, flavors) {
+                errors.push(err);
+            }
+        }
+        let (Some(date), Some(roles), Some(flavors)) = (date, roles, flavors) else {
+            return Err(errors);
+        };
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        let mut event = Event::new(creator, self.title.trim().to_string(), date, server_id);
+        event.description = self.description.clone().unwrap_or_default();
+        for role in roles {
+            event.add_role(role);
+        }
+        for flavor in flavors {
+            event.add_flavor(flavor);
+        }
+        Ok(event)
+    }
+}
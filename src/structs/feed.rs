@@ -0,0 +1,26 @@
+use poise::serenity_prelude::{GuildId, UserId};
+
+/// Whose events a calendar feed lists.
+#[derive(Clone, Copy, PartialEq)]
+pub enum FeedOwner {
+    /// The events the user signed up for, across all servers.
+    User(UserId),
+    /// Every event of the server.
+    Guild(GuildId),
+}
+
+impl FeedOwner {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            FeedOwner::User(_) => "user",
+            FeedOwner::Guild(_) => "guild",
+        }
+    }
+
+    pub fn id(&self) -> u64 {
+        match self {
+            FeedOwner::User(user_id) => user_id.get(),
+            FeedOwner::Guild(guild_id) => guild_id.get(),
+        }
+    }
+}
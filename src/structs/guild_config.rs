@@ -0,0 +1,58 @@
+use chrono::{Duration, Locale};
+use chrono_tz::Tz;
+use poise::serenity_prelude::{ChannelId, Color, GuildId, RoleId};
+
+use super::reminders::ReminderSettings;
+
+pub const DEFAULT_LOCALE: Locale = Locale::en_US;
+
+/// Per-server settings, changed with `/config`.
+#[derive(Clone)]
+pub struct GuildConfig {
+    pub guild_id: GuildId,
+    /// Channel new events are posted to. Without one, events are posted where
+    /// the command was used.
+    pub event_channel: Option<ChannelId>,
+    /// Zone for date input of members who have not picked their own.
+    pub timezone: Option<Tz>,
+    /// Language of the dates the bot writes out itself.
+    pub locale: Locale,
+    /// Reminder offsets of new events, largest first.
+    pub reminder_offsets: Vec<Duration>,
+    /// Role whose members may organize events.
+    pub organizer_role: Option<RoleId>,
+    pub embed_color: Color,
+    /// Whether every new event gets a channel of its own.
+    pub own_channel: bool,
+    /// Category the channels of events are created in.
+    pub channel_category: Option<ChannelId>,
+    /// Category the channels of past events are moved to. Without one, they
+    /// are deleted instead.
+    pub archive_category: Option<ChannelId>,
+    /// How long after the end of an event its channel is cleaned up.
+    pub channel_cleanup: Duration,
+    /// Channel that receives the transcripts of deleted event channels.
+    pub log_channel: Option<ChannelId>,
+}
+
+/// How long the channel of an event stays around after it ended, by default.
+pub const DEFAULT_CHANNEL_CLEANUP: Duration = Duration::hours(24);
+
+impl GuildConfig {
+    pub fn new(guild_id: GuildId) -> GuildConfig {
+        GuildConfig {
+            guild_id,
+            event_channel: None,
+            timezone: None,
+            locale: DEFAULT_LOCALE,
+            reminder_offsets: ReminderSettings::default().offsets,
+            organizer_role: None,
+            embed_color: Color::PURPLE,
+            own_channel: false,
+            channel_category: None,
+            archive_category: None,
+            channel_cleanup: DEFAULT_CHANNEL_CLEANUP,
+            log_channel: None,
+        }
+    }
+}
@@ -1,2 +1,12 @@
+pub mod attendance;
 pub mod client_structs;
 pub mod event;
+pub mod event_button;
+pub mod event_error;
+pub mod event_modal;
+pub mod feed;
+pub mod guild_config;
+pub mod recurrence;
+pub mod reminders;
+pub mod series;
+pub mod template;
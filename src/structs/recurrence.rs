@@ -0,0 +1,199 @@
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+
+#[derive(Clone, Copy, PartialEq, poise::ChoiceParameter)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+}
+
+impl Frequency {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Frequency::Daily => "daily",
+            Frequency::Weekly => "weekly",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum RecurrenceEnd {
+    Never,
+    Until(DateTime<Utc>),
+    Count(u32),
+}
+
+#[derive(Clone)]
+pub struct Recurrence {
+    pub frequency: Frequency,
+    /// Repeat every `interval` days or weeks.
+    pub interval: u32,
+    /// Weekdays of a weekly recurrence. Empty means the weekday of the first occurrence.
+    pub weekdays: Vec<Weekday>,
+    pub end: RecurrenceEnd,
+    /// Zone whose wall-clock time and weekdays the occurrences follow.
+    pub zone: Tz,
+}
+
+impl Recurrence {
+    fn matches(&self, first: NaiveDate, day: NaiveDate) -> bool {
+        if day == first {
+            return true;
+        }
+        let interval = i64::from(self.interval.max(1));
+        match self.frequency {
+            Frequency::Daily => (day - first).num_days() % interval == 0,
+            Frequency::Weekly => {
+                let weeks = (day.week(Weekday::Mon).first_day()
+                    - first.week(Weekday::Mon).first_day())
+                .num_weeks();
+                let weekday_matches = if self.weekdays.is_empty() {
+                    day.weekday() == first.weekday()
+                } else {
+                    self.weekdays.contains(&day.weekday())
+                };
+                weeks % interval == 0 && weekday_matches
+            }
+        }
+    }
+
+    /// Fails if the recurrence ends before `first`, which would leave the
+    /// series without any occurrence.
+    pub fn check_start(&self, first: DateTime<Utc>) -> Result<(), String> {
+        match self.end {
+            RecurrenceEnd::Until(until) if until < first => Err(format!(
+                "The series would end on {}, before its first event.",
+                until.with_timezone(&self.zone).format("%Y-%m-%d")
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Start times of all occurrences, beginning with `first`. Occurrences keep
+    /// the same wall-clock time in the zone of the recurrence.
+    pub fn occurrences(&self, first: DateTime<Utc>) -> impl Iterator<Item = DateTime<Utc>> + '_ {
+        let first = first.with_timezone(&self.zone);
+        let first_day = first.date_naive();
+        let time = first.time();
+        let count = match self.end {
+            RecurrenceEnd::Count(count) => count as usize,
+            _ => usize::MAX,
+        };
+        first_day
+            .iter_days()
+            .filter(move |day| self.matches(first_day, *day))
+            .filter_map(move |day| {
+                self.zone
+                    .from_local_datetime(&day.and_time(time))
+                    .earliest()
+            })
+            .map(|date| date.to_utc())
+            .take_while(move |date| match self.end {
+                RecurrenceEnd::Until(until) => *date <= until,
+                _ => true,
+            })
+            .take(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::Europe::Berlin;
+
+    fn berlin(year: i32, month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+        Berlin
+            .with_ymd_and_hms(year, month, day, hour, 0, 0)
+            .unwrap()
+            .to_utc()
+    }
+
+    fn weekly(interval: u32, weekdays: Vec<Weekday>, end: RecurrenceEnd) -> Recurrence {
+        Recurrence {
+            frequency: Frequency::Weekly,
+            interval,
+            weekdays,
+            end,
+            zone: Berlin,
+        }
+    }
+
+    #[test]
+    fn every_other_week_on_several_weekdays() {
+        let recurrence = weekly(2, vec![Weekday::Mon, Weekday::Thu], RecurrenceEnd::Never);
+        let dates = recurrence
+            .occurrences(berlin(2026, 3, 2, 19))
+            .take(5)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            dates,
+            vec![
+                berlin(2026, 3, 2, 19),
+                berlin(2026, 3, 5, 19),
+                berlin(2026, 3, 16, 19),
+                berlin(2026, 3, 19, 19),
+                berlin(2026, 3, 30, 19),
+            ]
+        );
+    }
+
+    #[test]
+    fn first_occurrence_counts_off_the_weekdays() {
+        let recurrence = weekly(1, vec![Weekday::Fri], RecurrenceEnd::Count(2));
+        let dates = recurrence
+            .occurrences(berlin(2026, 3, 4, 19))
+            .collect::<Vec<_>>();
+        assert_eq!(dates, vec![berlin(2026, 3, 4, 19), berlin(2026, 3, 6, 19)]);
+    }
+
+    #[test]
+    fn until_includes_an_occurrence_at_the_end() {
+        let recurrence = Recurrence {
+            frequency: Frequency::Daily,
+            interval: 3,
+            weekdays: Vec::new(),
+            end: RecurrenceEnd::Until(berlin(2026, 3, 7, 19)),
+            zone: Berlin,
+        };
+        let dates = recurrence
+            .occurrences(berlin(2026, 3, 1, 19))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            dates,
+            vec![
+                berlin(2026, 3, 1, 19),
+                berlin(2026, 3, 4, 19),
+                berlin(2026, 3, 7, 19)
+            ]
+        );
+    }
+
+    #[test]
+    fn refuses_to_end_before_the_first_occurrence() {
+        let mut recurrence = weekly(1, Vec::new(), RecurrenceEnd::Until(berlin(2026, 3, 1, 23)));
+        assert!(recurrence.check_start(berlin(2026, 3, 2, 19)).is_err());
+        recurrence.end = RecurrenceEnd::Until(berlin(2026, 3, 2, 23));
+        assert!(recurrence.check_start(berlin(2026, 3, 2, 19)).is_ok());
+    }
+
+    #[test]
+    fn count_limits_the_occurrences() {
+        let recurrence = weekly(1, Vec::new(), RecurrenceEnd::Count(3));
+        assert_eq!(recurrence.occurrences(berlin(2026, 3, 2, 19)).count(), 3);
+    }
+
+    #[test]
+    fn keeps_the_wall_clock_time_across_dst() {
+        let recurrence = weekly(1, Vec::new(), RecurrenceEnd::Count(2));
+        let dates = recurrence
+            .occurrences(berlin(2026, 3, 22, 19))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            dates,
+            vec![
+                Utc.with_ymd_and_hms(2026, 3, 22, 18, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2026, 3, 29, 17, 0, 0).unwrap(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,23 @@
+use chrono::Duration;
+use poise::serenity_prelude::RoleId;
+
+/// When and how participants are reminded of an upcoming event.
+#[derive(Clone)]
+pub struct ReminderSettings {
+    /// How long before the start a reminder is sent, largest first.
+    pub offsets: Vec<Duration>,
+    /// Whether every participant gets the reminders as a DM.
+    pub dm: bool,
+    /// Role pinged in the event channel with every reminder.
+    pub ping_role: Option<RoleId>,
+}
+
+impl Default for ReminderSettings {
+    fn default() -> Self {
+        ReminderSettings {
+            offsets: vec![Duration::hours(24), Duration::minutes(15)],
+            dm: true,
+            ping_role: None,
+        }
+    }
+}
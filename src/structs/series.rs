@@ -0,0 +1,82 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use poise::serenity_prelude::ChannelId;
+use uuid::Uuid;
+
+use super::event::{Event, Occurrence};
+use super::recurrence::{Recurrence, RecurrenceEnd};
+
+/// A recurring event. New occurrences are created from the template ahead of time.
+#[derive(Clone)]
+pub struct Series {
+    pub id: Uuid,
+    /// Title, description, roles and flavors shared by every occurrence. Its
+    /// date is the start of the first occurrence.
+    pub template: Event,
+    pub recurrence: Recurrence,
+    /// Channel the embeds of new occurrences are posted to.
+    pub channel_id: ChannelId,
+    /// Channel created for the series alone, cleaned up once the series ends.
+    pub own_channel: Option<ChannelId>,
+    /// Day of the last occurrence that was created, in the zone of the recurrence.
+    pub generated_until: NaiveDate,
+}
+
+impl Series {
+    /// Starts a series with `first` as its first occurrence.
+    pub fn new(first: &mut Event, recurrence: Recurrence, channel_id: ChannelId) -> Series {
+        let id = Uuid::new_v4();
+        // The channel outlives the first occurrence, so the series owns it.
+        let own_channel = first.own_channel.take();
+        first.series = Some(Occurrence {
+            series_id: id,
+            scheduled: first.date,
+        });
+        let mut template = first.clone();
+        template.id = id;
+        template.series = None;
+        template.participants.clear();
+        template.waitlist.clear();
+        template.event_messages.clear();
+        template.scheduled_event = None;
+        template.thread = None;
+        template.voice = None;
+        Series {
+            id,
+            generated_until: first.date.with_timezone(&recurrence.zone).date_naive(),
+            template,
+            recurrence,
+            channel_id,
+            own_channel,
+        }
+    }
+
+    /// Start times of occurrences up to `horizon` that have not been created yet.
+    pub fn due_occurrences(&self, horizon: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+        self.recurrence
+            .occurrences(self.template.date)
+            .take_while(|date| *date <= horizon)
+            .filter(|date| {
+                date.with_timezone(&self.recurrence.zone).date_naive() > self.generated_until
+            })
+            .collect()
+    }
+
+    /// Start of the last occurrence, or `None` if the series never ends.
+    pub fn last_occurrence(&self) -> Option<DateTime<Utc>> {
+        match self.recurrence.end {
+            RecurrenceEnd::Never => None,
+            _ => self.recurrence.occurrences(self.template.date).last(),
+        }
+    }
+
+    pub fn occurrence(&self, scheduled: DateTime<Utc>) -> Event {
+        let mut event = self.template.clone();
+        event.id = Uuid::new_v4();
+        event.date = scheduled;
+        event.series = Some(Occurrence {
+            series_id: self.id,
+            scheduled,
+        });
+        event
+    }
+}
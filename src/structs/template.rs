@@ -0,0 +1,29 @@
+use chrono::Duration;
+use poise::serenity_prelude::GuildId;
+
+use super::event::{Event, Role, RoleFlavor};
+
+/// Role composition and defaults of an event, saved under a name so new
+/// events of the server can start from it.
+#[derive(Clone)]
+pub struct EventTemplate {
+    pub guild_id: GuildId,
+    pub name: String,
+    pub description: String,
+    pub roles: Vec<Role>,
+    pub flavors: Vec<RoleFlavor>,
+    pub duration: Duration,
+}
+
+impl EventTemplate {
+    pub fn from_event(guild_id: GuildId, name: String, event: &Event) -> EventTemplate {
+        EventTemplate {
+            guild_id,
+            name,
+            description: event.description.clone(),
+            roles: event.needed_roles.clone(),
+            flavors: event.needed_flavors.clone(),
+            duration: event.duration,
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use chrono::{DateTime, Utc};
+use poise::serenity_prelude::{
+    self as serenity, ChannelId, ChannelType, Context, CreateAttachment, CreateChannel,
+    CreateMessage, EditChannel, GetMessages, GuildChannel, Message, PermissionOverwrite,
+    PermissionOverwriteType, Permissions, RoleId,
+};
+
+use crate::structs::event::{Event, EventState};
+use crate::structs::guild_config::GuildConfig;
+use crate::structs::series::Series;
+
+/// Messages Discord hands out per request.
+const PAGE_SIZE: u8 = 100;
+
+/// Creates a channel for the event alone in the configured category. It keeps
+/// the overwrites of the category and makes sure the bot and the creator can
+/// manage it.
+pub async fn create_event_channel(
+    ctx: &Context,
+    config: &GuildConfig,
+    event: &Event,
+) -> serenity::Result<ChannelId> {
+    let mut builder = CreateChannel::new(&event.title).kind(ChannelType::Text);
+    let mut overwrites = Vec::new();
+    if let Some(category) = config.channel_category {
+        builder = builder.category(category);
+        if let Some(category) = category.to_channel(ctx).await?.guild() {
+            overwrites = category.permission_overwrites;
+        }
+    }
+    overwrites.push(PermissionOverwrite {
+        allow: Permissions::VIEW_CHANNEL
+            | Permissions::SEND_MESSAGES
+            | Permissions::EMBED_LINKS
+            | Permissions::READ_MESSAGE_HISTORY
+            | Permissions::MANAGE_CHANNELS
+            | Permissions::MANAGE_MESSAGES
+            | Permissions::CREATE_PUBLIC_THREADS
+            | Permissions::MANAGE_THREADS,
+        deny: Permissions::empty(),
+        kind: PermissionOverwriteType::Member(ctx.cache.current_user().id),
+    });
+    overwrites.push(PermissionOverwrite {
+        allow: Permissions::VIEW_CHANNEL
+            | Permissions::SEND_MESSAGES
+            | Permissions::MANAGE_MESSAGES,
+        deny: Permissions::empty(),
+        kind: PermissionOverwriteType::Member(event.creator.id),
+    });
+    Ok(config
+        .guild_id
+        .create_channel(ctx, builder.permissions(overwrites))
+        .await?
+        .id)
+}
+
+/// Whether the channel of the event is due to be archived or deleted.
+pub fn channel_due(event: &Event, config: &GuildConfig, now: DateTime<Utc>) -> bool {
+    event.own_channel.is_some()
+        && matches!(event.state(), EventState::Completed | EventState::Cancelled)
+        && event.end() + config.channel_cleanup <= now
+}
+
+/// Whether the channel of the series is due to be archived or deleted, i.e.
+/// its last occurrence ended a while ago.
+pub fn series_channel_due(series: &Series, config: &GuildConfig, now: DateTime<Utc>) -> bool {
+    series.own_channel.is_some()
+        && series
+            .last_occurrence()
+            .is_some_and(|last| last + series.template.duration + config.channel_cleanup <= now)
+}
+
+/// Moves the channel to the archive category and makes it read only, or posts
+/// a transcript to the log channel and deletes it when there is no archive
+/// category. Channels deleted by hand are simply skipped.
+pub async fn clean_up_channel(
+    ctx: &Context,
+    config: &GuildConfig,
+    channel_id: ChannelId,
+    title: &str,
+) -> serenity::Result<()> {
+    let channel = match channel_id.to_channel(ctx).await {
+        Ok(channel) => channel.guild(),
+        Err(err) if is_not_found(&err) => None,
+        Err(err) => return Err(err),
+    };
+    if let Some(channel) = channel {
+        match config.archive_category {
+            Some(archive) => archive_channel(ctx, channel, archive).await?,
+            None => {
+                if let Some(log_channel) = config.log_channel {
+                    post_transcript(ctx, &channel, title, log_channel).await?;
+                }
+                channel.delete(ctx).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn archive_channel(
+    ctx: &Context,
+    mut channel: GuildChannel,
+    archive: ChannelId,
+) -> serenity::Result<()> {
+    let everyone = PermissionOverwriteType::Role(RoleId::new(channel.guild_id.get()));
+    let mut overwrites = channel.permission_overwrites.clone();
+    match overwrites.iter_mut().find(|ow| ow.kind == everyone) {
+        Some(overwrite) => {
+            overwrite.allow.remove(Permissions::SEND_MESSAGES);
+            overwrite.deny.insert(Permissions::SEND_MESSAGES);
+        }
+        None => overwrites.push(PermissionOverwrite {
+            allow: Permissions::empty(),
+            deny: Permissions::SEND_MESSAGES,
+            kind: everyone,
+        }),
+    }
+    channel
+        .edit(
+            ctx,
+            EditChannel::new().category(archive).permissions(overwrites),
+        )
+        .await
+}
+
+async fn post_transcript(
+    ctx: &Context,
+    channel: &GuildChannel,
+    title: &str,
+    log_channel: ChannelId,
+) -> serenity::Result<()> {
+    let mut messages = Vec::new();
+    let mut request = GetMessages::new().limit(PAGE_SIZE);
+    loop {
+        let page = channel.id.messages(ctx, request).await?;
+        let done = page.len() < PAGE_SIZE as usize;
+        if let Some(oldest) = page.last() {
+            request = GetMessages::new().limit(PAGE_SIZE).before(oldest.id);
+        }
+        messages.extend(page);
+        if done {
+            break;
+        }
+    }
+    let transcript = messages
+        .iter()
+        .rev()
+        .map(transcript_line)
+        .collect::<Vec<String>>()
+        .join("\n");
+    log_channel
+        .send_message(
+            ctx,
+            CreateMessage::new()
+                .content(format!(
+                    "Transcript of #{} from **{}**, deleted after the event.",
+                    channel.name, title
+                ))
+                .add_file(CreateAttachment::bytes(
+                    transcript,
+                    format!("{}.txt", channel.name),
+                )),
+        )
+        .await?;
+    Ok(())
+}
+
+fn transcript_line(message: &Message) -> String {
+    let time = DateTime::from_timestamp(message.timestamp.unix_timestamp(), 0)
+        .unwrap_or_default()
+        .format("%Y-%m-%d %H:%M");
+    let mut line = format!("[{time} UTC] {}: {}", message.author.name, message.content);
+    for embed in &message.embeds {
+        line += &format!(" [embed: {}]", embed.title.as_deref().unwrap_or("untitled"));
+    }
+    for attachment in &message.attachments {
+        line += &format!(" [attachment: {}]", attachment.url);
+    }
+    line
+}
+
+/// Whether the request failed because the channel no longer exists.
+pub fn is_not_found(err: &serenity::Error) -> bool {
+    matches!(err, serenity::Error::Http(err) if err.status_code().is_some_and(|code| code.as_u16() == 404))
+}
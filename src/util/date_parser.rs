@@ -0,0 +1,316 @@
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc,
+    Weekday,
+};
+use chrono_tz::Tz;
+
+use super::event_parser::{DATE_FORMAT, DAY_FORMAT, TIME_FORMAT};
+
+const USAGE: &str =
+    "Use e.g. `2026-11-03 19:30`, `next friday 8pm`, `tomorrow 21:15 CET` or `in 3 days`.";
+
+/// Zone abbreviations people commonly type, with their offset from UTC in minutes.
+const ABBREVIATIONS: &[(&str, i32)] = &[
+    ("utc", 0),
+    ("gmt", 0),
+    ("z", 0),
+    ("wet", 0),
+    ("west", 60),
+    ("bst", 60),
+    ("cet", 60),
+    ("cest", 120),
+    ("eet", 120),
+    ("eest", 180),
+    ("msk", 180),
+    ("jst", 540),
+    ("aest", 600),
+    ("aedt", 660),
+    ("est", -300),
+    ("edt", -240),
+    ("cdt", -300),
+    ("mst", -420),
+    ("mdt", -360),
+    ("pst", -480),
+    ("pdt", -420),
+];
+
+/// Abbreviations several zones share, with the zones to name instead.
+const AMBIGUOUS: &[(&str, &str)] = &[
+    ("ist", "`Asia/Kolkata`, `Asia/Jerusalem` or `Europe/Dublin`"),
+    ("cst", "`America/Chicago` or `Asia/Shanghai`"),
+];
+
+/// A zone named at the end of the input.
+enum Zone {
+    Named(Tz),
+    Fixed(FixedOffset),
+}
+
+impl Zone {
+    /// The zone the word names, if any. Ambiguous abbreviations are refused.
+    fn parse(word: &str) -> Result<Option<Zone>, String> {
+        if word.contains('/') {
+            return Ok(Tz::from_str_insensitive(word).ok().map(Zone::Named));
+        }
+        let lower = word.to_lowercase();
+        if let Some((_, zones)) = AMBIGUOUS.iter().find(|(name, _)| *name == lower) {
+            return Err(format!(
+                "`{word}` could mean several timezones. Use {zones} instead."
+            ));
+        }
+        Ok(ABBREVIATIONS
+            .iter()
+            .find(|(name, _)| *name == lower)
+            .and_then(|(_, minutes)| FixedOffset::east_opt(minutes * 60))
+            .map(Zone::Fixed))
+    }
+}
+
+/// Parses a start time such as `2026-11-03 19:30`, `next friday 8pm`,
+/// `tomorrow 21:15 CET` or `in 3 days`. Times are read in `zone` unless the
+/// input names its own zone, and must lie after `now`.
+pub fn parse_date(input: &str, zone: Tz, now: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    let mut words = input.split_whitespace().collect::<Vec<&str>>();
+    let explicit = match words.last() {
+        Some(word) => Zone::parse(word)?,
+        None => None,
+    };
+    if explicit.is_some() {
+        words.pop();
+    }
+    if words.is_empty() {
+        return Err(format!("Please enter a date. {USAGE}"));
+    }
+    let words = words
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<String>>();
+    let words = words.iter().map(String::as_str).collect::<Vec<&str>>();
+    let date = match explicit {
+        Some(Zone::Named(tz)) => resolve(&words, &tz, now),
+        Some(Zone::Fixed(offset)) => resolve(&words, &offset, now),
+        None => resolve(&words, &zone, now),
+    }?;
+    if date <= now {
+        return Err(format!(
+            "`{}` is in the past. The event date must be in the future.",
+            input.trim()
+        ));
+    }
+    Ok(date)
+}
+
+fn resolve<Z: TimeZone>(
+    words: &[&str],
+    zone: &Z,
+    now: DateTime<Utc>,
+) -> Result<DateTime<Utc>, String> {
+    let today = now.with_timezone(zone).date_naive();
+    let (day, time) = match words {
+        ["in", amount, unit, time @ ..] => {
+            let span = parse_span(amount, unit)?;
+            if time.is_empty() {
+                return Ok(now + span);
+            }
+            if span < Duration::days(1) {
+                return Err(String::from(
+                    "A time of day can only follow a number of days or weeks.",
+                ));
+            }
+            (today + span, time)
+        }
+        ["today", time @ ..] => (today, time),
+        ["tomorrow", time @ ..] => (today + Duration::days(1), time),
+        ["next", weekday, time @ ..] => (next_weekday(today, parse_weekday(weekday)?, 1), time),
+        [first, time @ ..] => {
+            if let Ok(day) = NaiveDate::parse_from_str(first, DAY_FORMAT) {
+                (day, time)
+            } else if let Ok(weekday) = first.parse::<Weekday>() {
+                (next_weekday(today, weekday, 0), time)
+            } else {
+                // Only a time of day was given.
+                (today, words)
+            }
+        }
+        [] => return Err(USAGE.to_string()),
+    };
+    let local = NaiveDateTime::new(day, parse_clock(time)?);
+    zone.from_local_datetime(&local)
+        .single()
+        .map(|date| date.to_utc())
+        .ok_or(format!(
+            "{} is ambiguous or does not exist because of a daylight saving change.",
+            local.format(DATE_FORMAT)
+        ))
+}
+
+fn parse_span(amount: &str, unit: &str) -> Result<Duration, String> {
+    let amount = amount
+        .parse::<u32>()
+        .map_err(|_| format!("`{amount}` is not a number."))?;
+    let amount = i64::from(amount);
+    match unit.trim_end_matches('s') {
+        "m" | "min" | "minute" => Ok(Duration::minutes(amount)),
+        "h" | "hr" | "hour" => Ok(Duration::hours(amount)),
+        "d" | "day" => Ok(Duration::days(amount)),
+        "w" | "week" => Ok(Duration::weeks(amount)),
+        _ => Err(format!(
+            "`{unit}` is not a unit. Use minutes, hours, days or weeks."
+        )),
+    }
+}
+
+fn parse_weekday(word: &str) -> Result<Weekday, String> {
+    word.parse::<Weekday>()
+        .map_err(|_| format!("`{word}` is not a weekday."))
+}
+
+/// First `weekday` at least `min_days` after `today`.
+fn next_weekday(today: NaiveDate, weekday: Weekday, min_days: i64) -> NaiveDate {
+    let ahead = (i64::from(weekday.num_days_from_monday())
+        - i64::from(today.weekday().num_days_from_monday())
+        - min_days)
+        .rem_euclid(7)
+        + min_days;
+    today + Duration::days(ahead)
+}
+
+/// Parses a time of day such as `19:30`, `8pm` or `at 8:30 pm`.
+fn parse_clock(words: &[&str]) -> Result<NaiveTime, String> {
+    let words = match words {
+        ["at", rest @ ..] => rest,
+        rest => rest,
+    };
+    let clock = words.concat();
+    if clock.is_empty() {
+        return Err(String::from(
+            "Please add a time of day, e.g. `8pm` or `20:00`.",
+        ));
+    }
+    let invalid = || format!("`{}` is not a valid time of day. {USAGE}", words.join(" "));
+    let (clock, afternoon) = match clock.strip_suffix("pm") {
+        Some(clock) => (clock, Some(true)),
+        None => match clock.strip_suffix("am") {
+            Some(clock) => (clock, Some(false)),
+            None => (clock.as_str(), None),
+        },
+    };
+    let Some(afternoon) = afternoon else {
+        return NaiveTime::parse_from_str(clock, TIME_FORMAT).map_err(|_| invalid());
+    };
+    let (hour, minute) = clock.split_once(':').unwrap_or((clock, "0"));
+    let hour = hour.parse::<u32>().map_err(|_| invalid())?;
+    let minute = minute.parse::<u32>().map_err(|_| invalid())?;
+    if !(1..=12).contains(&hour) {
+        return Err(invalid());
+    }
+    let hour = hour % 12 + if afternoon { 12 } else { 0 };
+    NaiveTime::from_hms_opt(hour, minute, 0).ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::America::New_York;
+    use chrono_tz::Europe::Berlin;
+
+    /// Wednesday, 13:00 in Berlin.
+    fn wednesday() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 4, 12, 0, 0).unwrap()
+    }
+
+    fn utc(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn parses_a_full_date() {
+        assert_eq!(
+            parse_date("2026-11-03 19:30", Berlin, wednesday()),
+            Ok(utc(2026, 11, 3, 18, 30))
+        );
+    }
+
+    #[test]
+    fn next_weekday_skips_today() {
+        assert_eq!(
+            parse_date("next friday 8pm", Berlin, wednesday()),
+            Ok(utc(2026, 3, 6, 19, 0))
+        );
+        let friday = utc(2026, 3, 6, 12, 0);
+        assert_eq!(
+            parse_date("next friday 8pm", Berlin, friday),
+            Ok(utc(2026, 3, 13, 19, 0))
+        );
+        assert_eq!(
+            parse_date("friday 8pm", Berlin, friday),
+            Ok(utc(2026, 3, 6, 19, 0))
+        );
+    }
+
+    #[test]
+    fn named_zone_overrides_the_default() {
+        assert_eq!(
+            parse_date("tomorrow 21:15 CET", New_York, wednesday()),
+            Ok(utc(2026, 3, 5, 20, 15))
+        );
+        assert_eq!(
+            parse_date("tomorrow 21:15 Europe/Berlin", New_York, wednesday()),
+            Ok(utc(2026, 3, 5, 20, 15))
+        );
+    }
+
+    #[test]
+    fn parses_spans() {
+        assert_eq!(
+            parse_date("in 3 days", Berlin, wednesday()),
+            Ok(wednesday() + Duration::days(3))
+        );
+        assert_eq!(
+            parse_date("in 2 weeks at 8:30 pm", Berlin, wednesday()),
+            Ok(utc(2026, 3, 18, 19, 30))
+        );
+        assert!(parse_date("in 3 hours 8pm", Berlin, wednesday()).is_err());
+    }
+
+    #[test]
+    fn twelve_am_and_pm() {
+        assert_eq!(
+            parse_date("tomorrow 12am", Berlin, wednesday()),
+            Ok(utc(2026, 3, 4, 23, 0))
+        );
+        assert_eq!(
+            parse_date("tomorrow 12pm", Berlin, wednesday()),
+            Ok(utc(2026, 3, 5, 11, 0))
+        );
+        assert!(parse_date("tomorrow 13pm", Berlin, wednesday()).is_err());
+    }
+
+    #[test]
+    fn rejects_the_past() {
+        let err = parse_date("2026-01-01 10:00", Berlin, wednesday()).unwrap_err();
+        assert!(err.contains("in the past"), "{err}");
+        assert!(parse_date("today 8am", Berlin, wednesday()).is_err());
+    }
+
+    #[test]
+    fn rejects_times_skipped_or_repeated_by_dst() {
+        let gap = parse_date("2026-03-29 02:30", Berlin, wednesday()).unwrap_err();
+        assert!(gap.contains("daylight saving"), "{gap}");
+        let overlap = parse_date("2026-10-25 02:30", Berlin, wednesday()).unwrap_err();
+        assert!(overlap.contains("daylight saving"), "{overlap}");
+        assert_eq!(
+            parse_date("2026-03-29 03:30", Berlin, wednesday()),
+            Ok(utc(2026, 3, 29, 1, 30))
+        );
+    }
+
+    #[test]
+    fn rejects_ambiguous_abbreviations() {
+        for zone in ["IST", "cst"] {
+            let err = parse_date(&format!("tomorrow 8pm {zone}"), Berlin, wednesday()).unwrap_err();
+            assert!(err.contains("several timezones"), "{err}");
+        }
+    }
+}
@@ -1,193 +1,121 @@
-use std::time::Duration;
+use crate::storage::event_store;
+use crate::structs::client_structs::Error;
+use crate::structs::event::{Event, EventState, Participant, RosterChanges, RsvpStatus};
+use crate::util::timezone::Clock;
+use poise::serenity_prelude::{Context, CreateMessage};
 
-use crate::structs::client_structs::Context as pContext;
-use crate::structs::event::{Role, RoleFlavor};
-use chrono::{DateTime, NaiveTime, Timelike};
-use poise::serenity_prelude::{futures::StreamExt, ComponentInteractionDataKind};
-use poise::serenity_prelude::{
-    CacheHttp, ChannelId, Context, CreateMessage, CreateSelectMenu, CreateSelectMenuKind,
-    CreateSelectMenuOption, MessageCollector,
-};
+pub const WAITLIST_REPLY: &str =
+    "The role is full, so you have been put on the waitlist. We'll let you know when a slot opens up.";
 
-pub async fn extract_role(
-    channel_id: ChannelId,
-    ctx: &Context,
-    first_prompt: Option<()>,
-) -> Option<Role> {
-    let mut message_stream = MessageCollector::new(ctx.shard.clone()).stream();
-    match first_prompt {
-        Some(_) => {
-            channel_id
-                .say(ctx.http(), String::from("Name a role for the event."))
-                .await
-                .unwrap();
-        }
-        None => {
-            channel_id
-                .say(
-                    ctx.http(),
-                    String::from("Do you want to create another role?"),
-                )
-                .await
-                .unwrap();
-        }
+/// Re-renders the posted embeds of the stored event and forgets the ones that
+/// were deleted in Discord.
+pub async fn publish(ctx: &Context, event: &Event) -> Result<(), Error> {
+    let deleted = event.update_event_messages(ctx).await;
+    if !deleted.is_empty() {
+        event_store(ctx).await.update(event.id, |event| {
+            event.forget_messages(&deleted);
+            Ok(())
+        })?;
+    }
+    Ok(())
+}
+
+/// Re-renders the posted embeds of every event that is still running, e.g.
+/// after a restart.
+pub async fn refresh_event_messages(ctx: Context) {
+    let events = match event_store(&ctx).await.all_events() {
+        Ok(events) => events,
+        Err(err) => return eprintln!("Could not load events to refresh: {err}"),
     };
-    message_stream.next().await;
-    let message = message_stream.next().await.unwrap();
-    if !message.content.trim().to_lowercase().contains("no") {
-        let message_stream = &mut message_stream;
-        channel_id
-            .say(
-                ctx.http(),
-                format!("How many {} will you need?", message.content),
-            )
-            .await
-            .unwrap();
-        message_stream.next().await;
-        let new_message = message_stream.next().await.unwrap();
-        if let Ok(num) = new_message.content.trim().parse::<u32>() {
-            return Some(Role {
-                name: message.content,
-                amount: num,
-            });
+    let running = events.iter().filter(|event| {
+        matches!(
+            event.state(),
+            EventState::Open | EventState::Locked | EventState::Started
+        )
+    });
+    for event in running {
+        if let Err(err) = publish(&ctx, event).await {
+            eprintln!("Could not refresh the messages of {}: {err}", event.id);
         }
     }
-    None
 }
 
-pub async fn extract_flavor(
-    channel_id: ChannelId,
+/// DMs every given member of the event the message built for them, with
+/// dates in their own timezone.
+pub async fn notify(
     ctx: &Context,
-    first_prompt: Option<()>,
-) -> Option<RoleFlavor> {
-    let mut message_stream = MessageCollector::new(ctx.shard.clone()).stream();
-    match first_prompt {
-        Some(_) => {
-            channel_id
-                .say(
-                    ctx.http(),
-                    String::from("Do you want to create a role-flavor for the event?"),
-                )
-                .await
-                .unwrap();
-        }
-        None => {
-            channel_id
-                .say(
-                    ctx.http(),
-                    String::from("Do you want to create another flavor?"),
-                )
-                .await
-                .unwrap();
-        }
-    };
-    message_stream.next().await;
-    let message = message_stream.next().await.unwrap();
-    if !message.content.trim().to_lowercase().contains("no") {
-        let message_stream = &mut message_stream;
-        channel_id
-            .say(
-                ctx.http(),
-                format!("How many {} will you need?", message.content),
+    event: &Event,
+    participants: &[Participant],
+    content: impl Fn(&Participant, &Clock) -> String,
+) {
+    let store = event_store(ctx).await;
+    for participant in participants {
+        let clock =
+            Clock::of(store.as_ref(), participant.id.id, Some(event.server_id)).unwrap_or_default();
+        // Members with closed DMs will still see the change on the embed.
+        let _ = participant
+            .id
+            .dm(
+                ctx,
+                CreateMessage::new().content(content(participant, &clock)),
             )
-            .await
-            .unwrap();
-        message_stream.next().await;
-        let new_message = message_stream.next().await.unwrap();
-        if let Ok(num) = new_message.content.trim().parse::<u32>() {
-            return Some(RoleFlavor {
-                flavor: message.content,
-                amount: num,
-            });
-        }
+            .await;
     }
-    None
 }
 
-pub async fn extract_datetime(channel_id: ChannelId, ctx: &pContext<'_>) -> DateTime<chrono::Utc> {
-    let handler = channel_id
-        .send_message(
-            ctx.http(),
-            CreateMessage::new()
-                .content("When is the event?")
-                .select_menu(CreateSelectMenu::new(
-                    "EventDate",
-                    CreateSelectMenuKind::String {
-                        options: [1, 2, 3, 4, 5, 6, 7]
-                            .iter()
-                            .map(|num| {
-                                let day = chrono::Local::now() + chrono::Duration::days(*num);
-                                CreateSelectMenuOption::new(
-                                    day.format("%A %B %d, %Y").to_string(),
-                                    day.format("%Y-%m-%d %H:%M:%S %z").to_string(),
-                                )
-                            })
-                            .collect(),
-                    },
-                )),
+/// Lets members who were moved up from the waitlist know about their slot.
+pub async fn notify_promoted(ctx: &Context, event: &Event, promoted: &[Participant]) {
+    notify(ctx, event, promoted, |prt, clock| {
+        format!(
+            "A slot opened up in **{}** on <t:{}:F> ({}): you have been moved from the waitlist onto the roster as {}.",
+            event.title,
+            event.date.timestamp(),
+            clock.format(event.date),
+            prt.role.name
         )
-        .await
-        .unwrap();
-    let interaction = handler
-        .await_component_interaction(ctx.serenity_context().shard.clone())
-        .timeout(Duration::from_secs(30))
-        .await
-        .unwrap();
-    let date = match &interaction.data.kind {
-        ComponentInteractionDataKind::StringSelect { values } => String::from(&values[0]),
-        _ => panic!("unexpected interaction data kind"),
-    };
-    handler.delete(&ctx).await.unwrap();
-    let selected_date = DateTime::parse_from_str(&date, "%Y-%m-%d %H:%M:%S %z")
-        .unwrap()
-        .to_utc();
-    let handler = channel_id
-        .send_message(
-            ctx.http(),
-            CreateMessage::new()
-                .content("At what time is the event?")
-                .select_menu(CreateSelectMenu::new(
-                    "EventTime",
-                    CreateSelectMenuKind::String {
-                        options: (0..24)
-                            .map(|num| {
-                                let day = chrono::Local::now()
-                                    .with_time(
-                                        NaiveTime::default()
-                                            .with_hour(0)
-                                            .unwrap()
-                                            .with_minute(0)
-                                            .unwrap()
-                                            .with_second(0)
-                                            .unwrap()
-                                            .with_nanosecond(0)
-                                            .unwrap(),
-                                    )
-                                    .unwrap()
-                                    + chrono::Duration::hours(num);
-                                CreateSelectMenuOption::new(
-                                    day.format("%H:%M").to_string(),
-                                    day.format("%Y-%m-%d %H:%M:%S %z").to_string(),
-                                )
-                            })
-                            .collect(),
-                    },
-                )),
+    })
+    .await;
+}
+
+/// Lets every member whose place changed after an edit of the roles know.
+pub async fn notify_roster_changes(ctx: &Context, event: &Event, changes: &RosterChanges) {
+    notify(ctx, event, &changes.removed, |prt, _| {
+        format!(
+            "**{}** no longer has the role {}, so you have been signed off.",
+            event.title, prt.role.name
         )
-        .await
-        .unwrap();
-    let interaction = handler
-        .await_component_interaction(ctx.serenity_context().shard.clone())
-        .timeout(Duration::from_secs(30))
-        .await
-        .unwrap();
-    let time = match &interaction.data.kind {
-        ComponentInteractionDataKind::StringSelect { values } => String::from(&values[0]),
-        _ => panic!("unexpected interaction data kind"),
+    })
+    .await;
+    notify(ctx, event, &changes.waitlisted, |prt, _| {
+        format!(
+            "**{}** now has fewer slots for {}, so you have been moved to the waitlist. We'll let you know when a slot opens up.",
+            event.title, prt.role.name
+        )
+    })
+    .await;
+    notify_promoted(ctx, event, &changes.promoted).await;
+}
+
+/// Tells everyone who is still planning to come that the event was cancelled.
+pub async fn notify_cancelled(ctx: &Context, event: &Event) {
+    let attending = event
+        .participants
+        .iter()
+        .chain(event.waitlist.iter())
+        .filter(|prt| prt.status != RsvpStatus::Declined)
+        .cloned()
+        .collect::<Vec<Participant>>();
+    let reason = match &event.cancel_reason {
+        Some(reason) => format!("\nReason: {reason}"),
+        None => String::new(),
     };
-    handler.delete(&ctx).await.unwrap();
-    let selected_time = DateTime::parse_from_str(&time, "%Y-%m-%d %H:%M:%S %z")
-        .unwrap()
-        .to_utc();
-    selected_date.with_time(selected_time.time()).unwrap()
+    notify(ctx, event, &attending, |_, clock| {
+        format!(
+            "**{}** on <t:{}:F> ({}) has been cancelled.{reason}",
+            event.title,
+            event.date.timestamp(),
+            clock.format(event.date)
+        )
+    })
+    .await;
 }
@@ -0,0 +1,358 @@
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+
+use crate::structs::event::{Role, RoleFlavor};
+use crate::structs::recurrence::{Frequency, Recurrence, RecurrenceEnd};
+
+pub const DATE_FORMAT: &str = "%Y-%m-%d %H:%M";
+pub const DAY_FORMAT: &str = "%Y-%m-%d";
+pub const TIME_FORMAT: &str = "%H:%M";
+
+/// Parses a role spec such as `Tank:2, Healer:2, DPS:6`.
+pub fn parse_roles(spec: &str) -> Result<Vec<Role>, String> {
+    let roles = parse_amounts(spec, "Role")?
+        .into_iter()
+        .map(|(name, amount)| Role { name, amount })
+        .collect::<Vec<Role>>();
+    if roles.is_empty() {
+        return Err(String::from("The event needs at least one role."));
+    }
+    Ok(roles)
+}
+
+/// Parses a flavor spec such as `DPS/Ranged:4, DPS/Melee:2+, Healer/Holy:1-2, Bard`.
+/// A `Role/` prefix limits the flavor to that role. `N` caps the flavor,
+/// `N+` asks for at least N members and `N-M` for both; without an amount
+/// (or with 0) any number of members may pick it. An empty spec means no flavors.
+pub fn parse_flavors(spec: &str) -> Result<Vec<RoleFlavor>, String> {
+    let mut flavors = Vec::<RoleFlavor>::new();
+    for entry in spec.split(',').map(str::trim).filter(|x| !x.is_empty()) {
+        let (name, amount) = match entry.rsplit_once(':') {
+            Some((name, amount)) => (name, Some(amount.trim())),
+            None => (entry, None),
+        };
+        let (role, name) = match name.split_once('/') {
+            Some((role, name)) => (Some(role.trim().to_string()), name.trim()),
+            None => (None, name.trim()),
+        };
+        if name.is_empty() || role.as_deref().is_some_and(str::is_empty) {
+            return Err(format!(
+                "`{entry}` needs a name, e.g. `Ranged:2` or `DPS/Ranged:2`."
+            ));
+        }
+        let (min, max) = match amount {
+            Some(amount) => parse_flavor_amount(amount).ok_or(format!(
+                "`{amount}` is not a valid amount for {name}. Use e.g. `4`, `2+` or `2-4`."
+            ))?,
+            None => (0, None),
+        };
+        if flavors
+            .iter()
+            .any(|flv| flv.flavor == name && flv.role == role)
+        {
+            return Err(format!("Flavor {name} is listed more than once."));
+        }
+        flavors.push(RoleFlavor {
+            flavor: name.to_string(),
+            role,
+            min,
+            max,
+        });
+    }
+    Ok(flavors)
+}
+
+/// The flavors that are left once the event only has `roles`.
+pub fn retain_flavors(roles: &[Role], flavors: &[RoleFlavor]) -> Vec<RoleFlavor> {
+    flavors
+        .iter()
+        .filter(|flv| {
+            flv.role
+                .as_ref()
+                .is_none_or(|role| roles.iter().any(|rl| rl.name == *role))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Reads `N`, `N+` or `N-M` into a minimum and an optional cap.
+fn parse_flavor_amount(amount: &str) -> Option<(u32, Option<u32>)> {
+    let (min, max) = if let Some(min) = amount.strip_suffix('+') {
+        (min.trim().parse().ok()?, None)
+    } else if let Some((min, max)) = amount.split_once('-') {
+        (min.trim().parse().ok()?, Some(max.trim().parse().ok()?))
+    } else {
+        (0, Some(amount.parse().ok()?))
+    };
+    let max = max.filter(|max| *max > 0);
+    if max.is_some_and(|max| max < min) {
+        return None;
+    }
+    Some((min, max))
+}
+
+/// Checks that the flavors fit the roles of the event: every role they name
+/// exists, no flavor is offered twice to a role and the minimums fit into
+/// the slots.
+pub fn check_flavors(roles: &[Role], flavors: &[RoleFlavor]) -> Result<(), String> {
+    for flavor in flavors {
+        let Some(role) = &flavor.role else {
+            continue;
+        };
+        if !roles.iter().any(|rl| rl.name == *role) {
+            return Err(format!(
+                "Flavor {} is for {role}, but the event has no such role.",
+                flavor.flavor
+            ));
+        }
+        if flavors
+            .iter()
+            .any(|flv| flv.role.is_none() && flv.flavor == flavor.flavor)
+        {
+            return Err(format!(
+                "Flavor {} is listed for {role} and for every role.",
+                flavor.flavor
+            ));
+        }
+    }
+    for role in roles {
+        let needed = flavors
+            .iter()
+            .filter(|flv| flv.role.as_ref() == Some(&role.name))
+            .map(|flv| flv.min)
+            .sum::<u32>();
+        if needed > role.amount {
+            return Err(format!(
+                "The flavors of {} need {needed} members, but the role only has {} slots.",
+                role.name, role.amount
+            ));
+        }
+    }
+    let needed = flavors
+        .iter()
+        .filter(|flv| flv.role.is_none())
+        .map(|flv| flv.min)
+        .sum::<u32>();
+    let slots = roles.iter().map(|rl| rl.amount).sum::<u32>();
+    if needed > slots {
+        return Err(format!(
+            "The flavors need {needed} members, but the event only has {slots} slots."
+        ));
+    }
+    Ok(())
+}
+
+/// Writes roles back in the format `parse_roles` reads.
+pub fn format_roles(roles: &[Role]) -> String {
+    roles
+        .iter()
+        .map(|role| format!("{}:{}", role.name, role.amount))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Writes flavors back in the format `parse_flavors` reads.
+pub fn format_flavors(flavors: &[RoleFlavor]) -> String {
+    flavors
+        .iter()
+        .map(|flavor| {
+            let name = match &flavor.role {
+                Some(role) => format!("{role}/{}", flavor.flavor),
+                None => flavor.flavor.clone(),
+            };
+            match (flavor.min, flavor.max) {
+                (0, None) => name,
+                (0, Some(max)) => format!("{name}:{max}"),
+                (min, None) => format!("{name}:{min}+"),
+                (min, Some(max)) => format!("{name}:{min}-{max}"),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn parse_amounts(spec: &str, kind: &str) -> Result<Vec<(String, u32)>, String> {
+    let mut entries = Vec::<(String, u32)>::new();
+    for entry in spec.split(',').map(str::trim).filter(|x| !x.is_empty()) {
+        let (name, amount) = entry
+            .rsplit_once(':')
+            .ok_or(format!("`{entry}` needs an amount, e.g. `{kind}:2`."))?;
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(format!("`{entry}` needs a name, e.g. `{kind}:2`."));
+        }
+        let amount = amount
+            .trim()
+            .parse::<u32>()
+            .map_err(|_| format!("`{}` is not a valid amount for {name}.", amount.trim()))?;
+        if entries.iter().any(|(existing, _)| existing == name) {
+            return Err(format!("{kind} {name} is listed more than once."));
+        }
+        entries.push((name.to_string(), amount));
+    }
+    Ok(entries)
+}
+
+/// Parses a `HH:MM` time of day.
+pub fn parse_time(input: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(input.trim(), TIME_FORMAT).map_err(|_| {
+        format!(
+            "`{}` is not a valid time. Use the format HH:MM.",
+            input.trim()
+        )
+    })
+}
+
+/// Moves `date` to `time` on the same day in `zone`.
+pub fn with_local_time(
+    date: DateTime<Utc>,
+    time: NaiveTime,
+    zone: Tz,
+) -> Result<DateTime<Utc>, String> {
+    zone.from_local_datetime(&date.with_timezone(&zone).date_naive().and_time(time))
+        .single()
+        .map(|date| date.to_utc())
+        .ok_or(format!(
+            "{} is ambiguous or does not exist on that day.",
+            time.format(TIME_FORMAT)
+        ))
+}
+
+/// Builds a recurrence rule from the options of `create_event`. Days and
+/// times follow `zone`.
+pub fn parse_recurrence(
+    frequency: Frequency,
+    every: Option<u32>,
+    weekdays: Option<&str>,
+    until: Option<&str>,
+    occurrences: Option<u32>,
+    zone: Tz,
+) -> Result<Recurrence, String> {
+    let interval = every.unwrap_or(1);
+    if interval == 0 {
+        return Err(String::from("An event can't repeat every 0 days or weeks."));
+    }
+    let weekdays = weekdays
+        .unwrap_or_default()
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|day| !day.is_empty())
+        .map(|day| {
+            day.parse::<Weekday>()
+                .map_err(|_| format!("`{day}` is not a weekday."))
+        })
+        .collect::<Result<Vec<Weekday>, String>>()?;
+    if !weekdays.is_empty() && frequency != Frequency::Weekly {
+        return Err(String::from(
+            "Weekdays can only be picked for weekly events.",
+        ));
+    }
+    let end = match (until, occurrences) {
+        (Some(_), Some(_)) => {
+            return Err(String::from(
+                "A series can end on a date or after a number of occurrences, not both.",
+            ))
+        }
+        (Some(until), None) => {
+            let day = NaiveDate::parse_from_str(until.trim(), DAY_FORMAT).map_err(|_| {
+                format!(
+                    "`{}` is not a valid date. Use the format YYYY-MM-DD.",
+                    until.trim()
+                )
+            })?;
+            let end_of_day = day.and_hms_opt(23, 59, 59).unwrap();
+            RecurrenceEnd::Until(
+                zone.from_local_datetime(&end_of_day)
+                    .latest()
+                    .ok_or(format!("`{}` does not exist.", until.trim()))?
+                    .to_utc(),
+            )
+        }
+        (None, Some(0)) => return Err(String::from("A series needs at least one occurrence.")),
+        (None, Some(count)) => RecurrenceEnd::Count(count),
+        (None, None) => RecurrenceEnd::Never,
+    };
+    Ok(Recurrence {
+        frequency,
+        interval,
+        weekdays,
+        end,
+        zone,
+    })
+}
+
+/// Parses a span such as `2d`, `24h` or `15m`.
+fn parse_span(entry: &str) -> Option<Duration> {
+    let unit_start = entry.len() - entry.chars().last().map_or(0, char::len_utf8);
+    let (amount, unit) = entry.split_at(unit_start);
+    let amount = amount.trim().parse::<u32>().ok()?;
+    match unit {
+        "d" => Some(Duration::days(amount.into())),
+        "h" => Some(Duration::hours(amount.into())),
+        "m" => Some(Duration::minutes(amount.into())),
+        _ => None,
+    }
+}
+
+/// Parses the length of an event such as `2h` or `90m`.
+pub fn parse_duration(input: &str) -> Result<Duration, String> {
+    match parse_span(input.trim()) {
+        Some(duration) if duration > Duration::zero() => Ok(duration),
+        _ => Err(format!(
+            "`{}` is not a valid length. Use e.g. `2h` or `90m`.",
+            input.trim()
+        )),
+    }
+}
+
+/// Parses reminder offsets such as `24h, 15m` or `2d`, largest first.
+pub fn parse_offsets(spec: &str) -> Result<Vec<Duration>, String> {
+    let mut offsets = spec
+        .split(',')
+        .map(str::trim)
+        .filter(|x| !x.is_empty())
+        .map(|entry| {
+            parse_span(entry).ok_or(format!(
+                "`{entry}` is not a valid reminder. Use e.g. `2d`, `24h` or `15m`."
+            ))
+        })
+        .collect::<Result<Vec<Duration>, String>>()?;
+    offsets.sort_by(|a, b| b.cmp(a));
+    offsets.dedup();
+    Ok(offsets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flavors_round_trip() {
+        let spec = "DPS/Ranged:4, DPS/Melee:2+, Healer/Holy:1-2, Bard";
+        assert_eq!(format_flavors(&parse_flavors(spec).unwrap()), spec);
+    }
+
+    #[test]
+    fn zero_means_any_number() {
+        let flavors = parse_flavors("Ranged:0, Melee:2-0").unwrap();
+        assert_eq!(format_flavors(&flavors), "Ranged, Melee:2+");
+    }
+
+    #[test]
+    fn rejects_bad_flavors() {
+        assert!(parse_flavors("DPS/:2").is_err());
+        assert!(parse_flavors("Ranged:4-2").is_err());
+        assert!(parse_flavors("Ranged:many").is_err());
+        assert!(parse_flavors("Ranged, Ranged:2").is_err());
+    }
+
+    #[test]
+    fn checks_flavors_against_roles() {
+        let roles = parse_roles("Tank:2, DPS:4").unwrap();
+        let check = |spec: &str| check_flavors(&roles, &parse_flavors(spec).unwrap());
+        assert!(check("DPS/Ranged:2+, DPS/Melee:2+, Leader:1+").is_ok());
+        assert!(check("Healer/Holy:1").is_err());
+        assert!(check("DPS/Ranged, Ranged").is_err());
+        assert!(check("DPS/Ranged:3+, DPS/Melee:2+").is_err());
+        assert!(check("Leader:7+").is_err());
+    }
+}
@@ -0,0 +1,90 @@
+use chrono::{DateTime, Utc};
+
+use crate::structs::event::{Event, EventState};
+
+const ICAL_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// Wraps the events into an iCalendar (RFC 5545) file.
+pub fn calendar(events: &[Event]) -> String {
+    let now = Utc::now();
+    let mut lines = vec![
+        String::from("BEGIN:VCALENDAR"),
+        String::from("VERSION:2.0"),
+        String::from("PRODID:-//event_bot//Discord events//EN"),
+        String::from("CALSCALE:GREGORIAN"),
+        String::from("METHOD:PUBLISH"),
+    ];
+    for event in events {
+        lines.extend(vevent(event, now));
+    }
+    lines.push(String::from("END:VCALENDAR"));
+    lines
+        .iter()
+        .map(|line| fold(line))
+        .collect::<Vec<String>>()
+        .concat()
+}
+
+/// The unfolded content lines of the VEVENT describing the event.
+fn vevent(event: &Event, now: DateTime<Utc>) -> Vec<String> {
+    let mut lines = vec![
+        String::from("BEGIN:VEVENT"),
+        format!("UID:{}@event_bot", event.id),
+        format!("DTSTAMP:{}", now.format(ICAL_FORMAT)),
+        format!("DTSTART:{}", event.date().format(ICAL_FORMAT)),
+        format!("DTEND:{}", event.end().format(ICAL_FORMAT)),
+        format!("SUMMARY:{}", escape(&event.title)),
+    ];
+    if !event.description.is_empty() {
+        lines.push(format!("DESCRIPTION:{}", escape(&event.description)));
+    }
+    lines.push(format!(
+        "ORGANIZER;CN=\"{}\":https://discord.com/users/{}",
+        event
+            .creator
+            .global_name
+            .as_ref()
+            .unwrap_or(&event.creator.name)
+            .replace('"', ""),
+        event.creator.id
+    ));
+    if let Some(link) = event.message_link() {
+        lines.push(format!("URL:{link}"));
+    }
+    lines.push(format!(
+        "STATUS:{}",
+        match event.state() {
+            EventState::Draft => "TENTATIVE",
+            EventState::Cancelled => "CANCELLED",
+            _ => "CONFIRMED",
+        }
+    ));
+    lines.push(String::from("END:VEVENT"));
+    lines
+}
+
+/// Escapes a TEXT value.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Splits a content line into lines of at most 75 octets, continued with a
+/// leading space, and ends it with CRLF.
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+    for ch in line.chars() {
+        if length + ch.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(ch);
+        length += ch.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
@@ -1 +1,10 @@
+pub mod channels;
+pub mod date_parser;
 pub mod event;
+pub mod event_parser;
+pub mod ical;
+pub mod permissions;
+pub mod scheduled_event;
+pub mod thread;
+pub mod timezone;
+pub mod voice;
@@ -0,0 +1,31 @@
+use crate::structs::client_structs::Context;
+use crate::structs::event::Event;
+use crate::structs::guild_config::GuildConfig;
+
+/// Whether the author administrates the server, which lets them manage every event.
+pub async fn is_admin(ctx: Context<'_>) -> bool {
+    ctx.author_member().await.is_some_and(|member| {
+        member
+            .permissions
+            .is_some_and(|permissions| permissions.administrator() || permissions.manage_guild())
+    })
+}
+
+/// Whether the author may create events. Servers without an organizer role
+/// let every member do so.
+pub async fn is_organizer(ctx: Context<'_>, config: &GuildConfig) -> bool {
+    let Some(role) = config.organizer_role else {
+        return true;
+    };
+    ctx.author_member()
+        .await
+        .is_some_and(|member| member.roles.contains(&role))
+        || is_admin(ctx).await
+}
+
+/// Whether the event belongs to the server the command was used in and the
+/// author organizes it or administrates the server.
+pub async fn can_manage(ctx: Context<'_>, event: &Event) -> bool {
+    ctx.guild_id() == Some(event.server_id())
+        && (event.is_organizer(ctx.author().id) || is_admin(ctx).await)
+}
@@ -0,0 +1,163 @@
+use chrono::Utc;
+use poise::serenity_prelude::{
+    CacheHttp, Context, CreateMessage, CreateScheduledEvent, EditScheduledEvent, GuildId,
+    ScheduledEventId, ScheduledEventStatus, ScheduledEventType, UserId,
+};
+
+use crate::storage::event_store;
+use crate::structs::client_structs::Error;
+use crate::structs::event::{Event, EventState};
+
+/// Discord cuts scheduled event descriptions off after this many characters.
+const DESCRIPTION_LIMIT: usize = 1000;
+
+/// Creates, updates or closes the Discord scheduled event mirroring the
+/// stored event, and remembers a newly created one on it. Failures, e.g. a
+/// missing Manage Events permission, are logged and otherwise ignored; the
+/// embeds stay the source of truth.
+pub async fn sync_scheduled_event(ctx: &Context, event: &mut Event) {
+    if !event.native_event {
+        return;
+    }
+    let guild_id = event.server_id();
+    let linked = event.scheduled_event;
+    let result = match (event.scheduled_event, event.state()) {
+        (None, EventState::Draft | EventState::Open | EventState::Locked)
+            if event.date() > Utc::now() =>
+        {
+            let builder =
+                CreateScheduledEvent::new(ScheduledEventType::External, &event.title, event.date())
+                    .end_time(event.end())
+                    .location(location(event))
+                    .description(description(event));
+            guild_id
+                .create_scheduled_event(ctx, builder)
+                .await
+                .map(|scheduled| event.scheduled_event = Some(scheduled.id))
+        }
+        (None, _) => Ok(()),
+        (Some(id), EventState::Draft | EventState::Open | EventState::Locked) => {
+            let builder = EditScheduledEvent::new()
+                .name(&event.title)
+                .description(description(event))
+                .start_time(event.date())
+                .end_time(event.end())
+                .location(location(event));
+            guild_id
+                .edit_scheduled_event(ctx, id, builder)
+                .await
+                .map(|_| ())
+        }
+        (Some(id), state) => {
+            let status = match state {
+                EventState::Started => ScheduledEventStatus::Active,
+                EventState::Completed => ScheduledEventStatus::Completed,
+                _ => ScheduledEventStatus::Canceled,
+            };
+            guild_id
+                .edit_scheduled_event(ctx, id, EditScheduledEvent::new().status(status))
+                .await
+                .map(|_| ())
+        }
+    };
+    if let Err(err) = result {
+        eprintln!(
+            "Could not sync the scheduled event of {}: {err}",
+            event.title
+        );
+    }
+    if event.scheduled_event != linked {
+        let scheduled_event = event.scheduled_event;
+        let stored = event_store(ctx).await.update(event.id, |stored| {
+            stored.scheduled_event = scheduled_event;
+            Ok(())
+        });
+        if let Err(err) = stored {
+            eprintln!(
+                "Could not link the scheduled event of {}: {err}",
+                event.title
+            );
+        }
+    }
+}
+
+/// Removes the Discord scheduled event mirroring the event, if any.
+pub async fn delete_scheduled_event(http: &impl CacheHttp, event: &mut Event) {
+    if let Some(id) = event.scheduled_event.take() {
+        // Members may have deleted it by hand already.
+        let _ = event
+            .server_id()
+            .delete_scheduled_event(http.http(), id)
+            .await;
+    }
+}
+
+fn location(event: &Event) -> String {
+    event
+        .message_link()
+        .unwrap_or_else(|| String::from("Discord"))
+}
+
+fn description(event: &Event) -> String {
+    event.description.chars().take(DESCRIPTION_LIMIT).collect()
+}
+
+/// Sends members who marked the scheduled event as interested the sign-up
+/// buttons of the event it mirrors.
+pub async fn offer_sign_up(
+    ctx: &Context,
+    guild_id: GuildId,
+    scheduled_event: ScheduledEventId,
+    user_id: UserId,
+) -> Result<(), Error> {
+    let Some(event) = find_event(ctx, guild_id, scheduled_event).await? else {
+        return Ok(());
+    };
+    if event.state() != EventState::Open || event.attends(user_id) {
+        return Ok(());
+    }
+    // Members with closed DMs can still sign up on the embed.
+    let _ = user_id
+        .to_user(ctx)
+        .await?
+        .dm(
+            ctx,
+            CreateMessage::new()
+                .content(format!(
+                    "You are interested in **{}** on <t:{}:F>. Pick a role to sign up:",
+                    event.title,
+                    event.date().timestamp()
+                ))
+                .components(event.components()),
+        )
+        .await;
+    Ok(())
+}
+
+/// Forgets the link to a scheduled event that was deleted in Discord.
+pub async fn unlink_scheduled_event(
+    ctx: &Context,
+    guild_id: GuildId,
+    scheduled_event: ScheduledEventId,
+) -> Result<(), Error> {
+    if let Some(event) = find_event(ctx, guild_id, scheduled_event).await? {
+        event_store(ctx).await.update(event.id, |event| {
+            event.native_event = false;
+            event.scheduled_event = None;
+            Ok(())
+        })?;
+    }
+    Ok(())
+}
+
+async fn find_event(
+    ctx: &Context,
+    guild_id: GuildId,
+    scheduled_event: ScheduledEventId,
+) -> Result<Option<Event>, Error> {
+    Ok(event_store(ctx)
+        .await
+        .events(guild_id)?
+        .into_iter()
+        .find(|ev| ev.scheduled_event == Some(scheduled_event)))
+}
@@ -0,0 +1,81 @@
+use poise::serenity_prelude::{
+    AutoArchiveDuration, CacheHttp, Context, CreateThread, EditThread, UserId,
+};
+
+use crate::storage::event_store;
+use crate::structs::event::{Event, EventState};
+
+/// Discord refuses thread names longer than this.
+const NAME_LIMIT: usize = 100;
+
+/// Opens the discussion thread on the first embed of the stored event, links
+/// it to the event and adds the creator. Failures, e.g. a missing Create
+/// Public Threads permission, are logged and leave the event without a thread.
+pub async fn open_thread(ctx: &Context, event: &mut Event) {
+    if !event.own_thread || event.thread.is_some() {
+        return;
+    }
+    let Some(posted) = event.event_messages.first() else {
+        return;
+    };
+    let name = event.title.chars().take(NAME_LIMIT).collect::<String>();
+    let thread = posted
+        .channel_id
+        .create_thread_from_message(
+            ctx,
+            posted.message_id,
+            CreateThread::new(name).auto_archive_duration(AutoArchiveDuration::OneWeek),
+        )
+        .await;
+    match thread {
+        Ok(thread) => {
+            event.thread = Some(thread.id);
+            let stored = event_store(ctx).await.update(event.id, |stored| {
+                stored.thread = Some(thread.id);
+                Ok(())
+            });
+            if let Err(err) = stored {
+                eprintln!("Could not link the thread of {}: {err}", event.title);
+            }
+            sync_thread_member(ctx, event, event.creator.id).await;
+        }
+        Err(err) => eprintln!("Could not open a thread for {}: {err}", event.title),
+    }
+}
+
+/// Adds the member to the discussion thread while they attend the event and
+/// removes them otherwise.
+pub async fn sync_thread_member(http: &impl CacheHttp, event: &Event, user_id: UserId) {
+    let Some(thread) = event.thread else {
+        return;
+    };
+    let result = if event.attends(user_id) || user_id == event.creator.id {
+        thread.add_thread_member(http.http(), user_id).await
+    } else {
+        thread.remove_thread_member(http.http(), user_id).await
+    };
+    if let Err(err) = result {
+        eprintln!("Could not update the thread of {}: {err}", event.title);
+    }
+}
+
+/// Archives the discussion thread once the event is over.
+pub async fn archive_thread(http: &impl CacheHttp, event: &Event) {
+    if matches!(event.state(), EventState::Completed | EventState::Cancelled) {
+        close_thread(http, event).await;
+    }
+}
+
+/// Archives the discussion thread whatever the state of the event, e.g.
+/// before the event is deleted.
+pub async fn close_thread(http: &impl CacheHttp, event: &Event) {
+    let Some(thread) = event.thread else {
+        return;
+    };
+    if let Err(err) = thread
+        .edit_thread(http, EditThread::new().archived(true))
+        .await
+    {
+        eprintln!("Could not archive the thread of {}: {err}", event.title);
+    }
+}
@@ -0,0 +1,62 @@
+use chrono::{DateTime, Locale, Utc};
+use chrono_tz::Tz;
+use poise::serenity_prelude::{GuildId, UserId};
+
+use crate::storage::EventStore;
+use crate::structs::client_structs::Error;
+use crate::structs::guild_config::DEFAULT_LOCALE;
+
+/// Zone used for date input when nothing else is configured: the zone of the
+/// host machine, or UTC if it can't be determined.
+pub fn default_timezone() -> Tz {
+    iana_time_zone::get_timezone()
+        .ok()
+        .and_then(|name| name.parse::<Tz>().ok())
+        .unwrap_or(Tz::UTC)
+}
+
+/// How dates are read and written for one member.
+#[derive(Clone, Copy)]
+pub struct Clock {
+    pub zone: Tz,
+    pub locale: Locale,
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Clock {
+            zone: default_timezone(),
+            locale: DEFAULT_LOCALE,
+        }
+    }
+}
+
+impl Clock {
+    /// Clock of the user on the given server. A zone the user set with
+    /// `/timezone set` wins over the one of the server.
+    pub fn of(
+        store: &dyn EventStore,
+        user_id: UserId,
+        guild_id: Option<GuildId>,
+    ) -> Result<Clock, Error> {
+        let config = guild_id.map(|id| store.guild_config(id)).transpose()?;
+        let zone = match store.user_timezone(user_id)? {
+            Some(zone) => zone,
+            None => config
+                .as_ref()
+                .and_then(|config| config.timezone)
+                .unwrap_or_else(default_timezone),
+        };
+        Ok(Clock {
+            zone,
+            locale: config.map_or(DEFAULT_LOCALE, |config| config.locale),
+        })
+    }
+
+    /// Wall-clock time of `date`, e.g. `Tue 2026-11-03 19:30 CET`.
+    pub fn format(&self, date: DateTime<Utc>) -> String {
+        date.with_timezone(&self.zone)
+            .format_localized("%a %Y-%m-%d %H:%M %Z", self.locale)
+            .to_string()
+    }
+}
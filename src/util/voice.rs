@@ -0,0 +1,133 @@
+use poise::serenity_prelude::{
+    self as serenity, CacheHttp, ChannelType, Context, CreateChannel, EditChannel,
+    PermissionOverwrite, PermissionOverwriteType, Permissions, RoleId, UserId,
+};
+
+use crate::structs::event::{Event, Participant, RsvpStatus};
+use crate::util::channels::is_not_found;
+
+/// Organizers and every participant who did not decline, plus the waitlist if
+/// the event lets it in.
+fn voice_members(event: &Event) -> Vec<UserId> {
+    let waitlist: &[Participant] = match event.voice_waitlist {
+        true => &event.waitlist,
+        false => &[],
+    };
+    let mut members = std::iter::once(event.creator.id)
+        .chain(event.co_organizers.iter().copied())
+        .chain(
+            event
+                .participants
+                .iter()
+                .chain(waitlist)
+                .filter(|prt| prt.status != RsvpStatus::Declined)
+                .map(|prt| prt.id.id),
+        )
+        .collect::<Vec<UserId>>();
+    members.sort();
+    members.dedup();
+    members
+}
+
+/// Hides the channel from everyone but the bot and the voice members.
+fn overwrites(ctx: &Context, event: &Event) -> Vec<PermissionOverwrite> {
+    let mut overwrites = vec![
+        PermissionOverwrite {
+            allow: Permissions::empty(),
+            deny: Permissions::VIEW_CHANNEL | Permissions::CONNECT,
+            kind: PermissionOverwriteType::Role(RoleId::new(event.server_id().get())),
+        },
+        PermissionOverwrite {
+            allow: Permissions::VIEW_CHANNEL
+                | Permissions::CONNECT
+                | Permissions::MANAGE_CHANNELS
+                | Permissions::MOVE_MEMBERS,
+            deny: Permissions::empty(),
+            kind: PermissionOverwriteType::Member(ctx.cache.current_user().id),
+        },
+    ];
+    overwrites.extend(
+        voice_members(event)
+            .into_iter()
+            .map(|user_id| PermissionOverwrite {
+                allow: Permissions::VIEW_CHANNEL
+                    | Permissions::CONNECT
+                    | Permissions::SPEAK
+                    | Permissions::STREAM,
+                deny: Permissions::empty(),
+                kind: PermissionOverwriteType::Member(user_id),
+            }),
+    );
+    overwrites
+}
+
+/// Creates the voice channel of the event next to the channel it was posted in.
+pub async fn open_voice_channel(ctx: &Context, event: &mut Event) -> serenity::Result<()> {
+    let mut builder = CreateChannel::new(&event.title)
+        .kind(ChannelType::Voice)
+        .permissions(overwrites(ctx, event));
+    let category = event.channel_id().and_then(|channel| {
+        ctx.cache
+            .guild(event.server_id())?
+            .channels
+            .get(&channel)?
+            .parent_id
+    });
+    if let Some(category) = category {
+        builder = builder.category(category);
+    }
+    let channel = event.server_id().create_channel(ctx, builder).await?;
+    event.voice = Some(channel.id);
+    Ok(())
+}
+
+/// Brings the overwrites of the voice channel in line with the roster, so
+/// members who signed up or off since the last check gain or lose access.
+pub async fn sync_voice_access(ctx: &Context, event: &Event) -> serenity::Result<()> {
+    let Some(voice) = event.voice else {
+        return Ok(());
+    };
+    let wanted = overwrites(ctx, event);
+    let current = ctx.cache.guild(event.server_id()).and_then(|guild| {
+        guild
+            .channels
+            .get(&voice)
+            .map(|channel| channel.permission_overwrites.clone())
+    });
+    if current.is_some_and(|current| {
+        current.len() == wanted.len() && wanted.iter().all(|ow| current.contains(ow))
+    }) {
+        return Ok(());
+    }
+    voice
+        .edit(ctx, EditChannel::new().permissions(wanted))
+        .await
+        .map(|_| ())
+}
+
+/// Whether anyone is connected to the voice channel. Without the guild in
+/// the cache, the channel is assumed to be in use.
+pub fn voice_occupied(ctx: &Context, event: &Event) -> bool {
+    let Some(voice) = event.voice else {
+        return false;
+    };
+    ctx.cache.guild(event.server_id()).is_none_or(|guild| {
+        guild
+            .voice_states
+            .values()
+            .any(|state| state.channel_id == Some(voice))
+    })
+}
+
+/// Deletes the voice channel of the event, if it still exists.
+pub async fn close_voice_channel(http: &impl CacheHttp, event: &mut Event) -> serenity::Result<()> {
+    if let Some(voice) = event.voice {
+        match voice.delete(http.http()).await {
+            Err(err) if !is_not_found(&err) => return Err(err),
+            _ => {}
+        }
+    }
+    event.voice = None;
+    event.voice_empty_since = None;
+    Ok(())
+}